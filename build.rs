@@ -1,7 +1,38 @@
+// Must match `EC_MAXGROUP` in `src/inner/soem_bindings`.
+const EC_MAXGROUP: u32 = 8;
+
+fn find_define(dir: &std::path::Path, name: &str) -> Option<String> {
+    std::fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
+        let path = entry.path();
+        if path.is_dir() {
+            return find_define(&path, name);
+        }
+        if path.extension().is_none_or(|ext| ext != "h") {
+            return None;
+        }
+        std::fs::read_to_string(&path)
+            .ok()?
+            .lines()
+            .find_map(|line| {
+                let mut tokens = line.split_whitespace();
+                (tokens.next() == Some("#define") && tokens.next() == Some(name))
+                    .then(|| tokens.next().unwrap_or_default().to_owned())
+            })
+    })
+}
+
 fn main() {
     println!("cargo:rerun-if-changed=3rdparty/SOEM");
 
-    let dst = cmake::build("3rdparty/SOEM");
+    let dst = cmake::Config::new("3rdparty/SOEM")
+        .define("EC_MAXGROUP", EC_MAXGROUP.to_string())
+        .build();
+    match find_define(&dst.join("include"), "EC_MAXGROUP") {
+        Some(value) if value == EC_MAXGROUP.to_string() => {}
+        value => panic!(
+            "SOEM was configured with EC_MAXGROUP = {value:?}, but the bindings expect {EC_MAXGROUP}"
+        ),
+    }
     println!(
         "cargo:rustc-link-search=native={}",
         dst.join("lib").display()
@@ -12,21 +12,84 @@ use std::{ffi::CString, time::Duration};
 
 use autd3_core::link::LinkError;
 
-use crate::inner::State;
+use crate::inner::{AlStatusCode, DeviceId, InterfaceCandidate, MacAddress, State};
 
+/// An error of the SOEM link.
+///
+/// The [`Link`] methods return this error converted into a [`LinkError`], which only keeps the message. The error of the last failed [`Link::open`] or [`Link::close`] is also kept as is, see [`SOEM::last_error`].
+///
+/// [`Link`]: autd3_core::link::Link
+/// [`Link::open`]: autd3_core::link::Link::open
+/// [`Link::close`]: autd3_core::link::Link::close
+/// [`SOEM::last_error`]: crate::SOEM::last_error
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum SOEMError {
+    /// The cycle is not a multiple of 500μs or is 0.
     InvalidCycle(Duration),
+    /// No AUTD device was found on the interface.
     NoDeviceFound,
+    /// The socket on the interface could not be opened.
     NoSocketConnection(CString),
+    /// The number of devices found on the bus (the first) differs from the number of devices in the geometry (the second).
     SlaveNotFound(u16, u16),
-    NotResponding,
-    NotReachedRequiredState(State, State),
+    /// The slaves did not respond. Each element is the position of the slave on the bus, its state, and its AL status code.
+    NotResponding(Vec<(u16, State, AlStatusCode)>),
+    /// The slaves did not reach the required state (the first). Each element is the position of the slave on the bus, its actual state, and its AL status code.
+    NotReachedRequiredState(State, Vec<(u16, State, AlStatusCode)>),
+    /// The interface name is invalid.
     InvalidInterfaceName(String),
+    /// The maximum system time difference between the devices (the first) exceeded the tolerance (the second).
     SynchronizeFailed(Duration, Duration),
+    /// Failed to set the priority of the EtherCAT thread.
     ThreadPriorityError(thread_priority::Error),
+    /// Failed to set the CPU affinity of the EtherCAT thread.
     AffinitySetFailed(core_affinity::CoreId),
+    /// The inputs of the devices were not refreshed in the latest cycle. Each element is the index of the device.
+    StaleInput(Vec<usize>),
+    /// The group index (the first) is not less than the maximum number of groups (the second).
+    InvalidGroup(usize, usize),
+    /// The number of device groups (the first) differs from the number of devices (the second).
+    GroupMismatch(usize, usize),
+    /// The process data size (the first) exceeds the size of the IOMap (the second) in bytes.
+    IOMapOverflow(usize, usize),
+    /// The slaves did not reach the requested state on shutdown. Each element is the position of the slave on the bus, the requested state, and its actual state.
+    ShutdownFailed(Vec<(u16, State, State)>),
+    /// The frames (the number of which is given) were not sent before the timeout.
+    FlushTimeout(usize),
+    /// The link was closed before the frames (the number of which is given) were sent.
+    FramesDropped(usize),
+    /// Neither the interface name nor the MAC address is specified for an interface of [`MultiSOEM`].
+    ///
+    /// [`MultiSOEM`]: crate::MultiSOEM
+    InterfaceNotSpecified,
+    /// AUTD devices were found on more than one interface in the automatic selection.
+    AmbiguousInterface(Vec<InterfaceCandidate>),
+    /// Probing the interfaces (the names of which are given) timed out in the automatic selection.
+    ProbeTimeout(Vec<String>),
+    /// No interface with the MAC address was found.
+    AdapterNotFound(MacAddress),
+    /// The process has no permission to open a raw socket.
+    PermissionDenied,
+    /// The interface was not found.
+    InterfaceNotFound(String),
+    /// The interface is down.
+    InterfaceDown(String),
+    /// No link is detected on the interface.
+    NoCarrier(String),
+    /// No AUTD device matches the id.
+    DeviceNotFound(DeviceId),
+    /// More than one AUTD device matches the id.
+    DuplicateDeviceId(DeviceId),
+    /// The number of device ids (the first) differs from the number of devices (the second).
+    DeviceIdMismatch(usize, usize),
+    /// The cycle of an interface (the second) differs from that of the first interface (the first).
+    CycleMismatch(Duration, Duration),
+    /// The slave at the position is an AUTD device.
+    NotForeignSlave(u16),
+    /// The process data size of the slave at the position (the second) differs from the size of the given data (the third).
+    PdoSizeMismatch(u16, usize, usize),
+    /// An I/O error.
     Io(std::io::Error),
 }
 
@@ -51,13 +114,21 @@ impl std::fmt::Display for SOEMError {
                     expected, found
                 )
             }
-            SOEMError::NotResponding => write!(f, "One ore more slaves are not responding"),
-            SOEMError::NotReachedRequiredState(expected, actual) => {
+            SOEMError::NotResponding(slaves) => {
+                write!(f, "One ore more slaves are not responding:")?;
+                slaves.iter().try_for_each(|(slave, state, code)| {
+                    write!(f, " Slave[{}] (state: {}, {})", slave, state, code)
+                })
+            }
+            SOEMError::NotReachedRequiredState(expected, slaves) => {
                 write!(
                     f,
-                    "One ore more slaves did not reach required state (expected: {}, actual: {})",
-                    expected, actual
-                )
+                    "One ore more slaves did not reach required state (expected: {}):",
+                    expected
+                )?;
+                slaves.iter().try_for_each(|(slave, state, code)| {
+                    write!(f, " Slave[{}] (actual: {}, {})", slave, state, code)
+                })
             }
             SOEMError::InvalidInterfaceName(name) => {
                 write!(f, "Invalid interface name: {}", name)
@@ -77,11 +148,156 @@ impl std::fmt::Display for SOEMError {
                     core_id
                 )
             }
+            SOEMError::StaleInput(devices) => {
+                write!(
+                    f,
+                    "Inputs of the devices {:?} were not refreshed in the latest cycle",
+                    devices
+                )
+            }
+            SOEMError::InvalidGroup(group, max) => {
+                write!(
+                    f,
+                    "Group index ({}) must be less than the maximum number of groups ({})",
+                    group, max
+                )
+            }
+            SOEMError::GroupMismatch(groups, devices) => {
+                write!(
+                    f,
+                    "The number of device groups you specified is {}, but {} devices are found",
+                    groups, devices
+                )
+            }
+            SOEMError::IOMapOverflow(size, capacity) => {
+                write!(
+                    f,
+                    "Process data ({} bytes) exceeds the size of the IOMap ({} bytes)",
+                    size, capacity
+                )
+            }
+            SOEMError::DeviceNotFound(id) => write!(f, "No AUTD device with {} was found", id),
+            SOEMError::DuplicateDeviceId(id) => {
+                write!(f, "{} matches more than one AUTD device", id)
+            }
+            SOEMError::DeviceIdMismatch(ids, devices) => {
+                write!(
+                    f,
+                    "The number of device ids you specified is {}, but {} devices are found",
+                    ids, devices
+                )
+            }
+            SOEMError::ShutdownFailed(slaves) => {
+                write!(
+                    f,
+                    "One or more slaves did not reach the requested state on shutdown:"
+                )?;
+                slaves.iter().try_for_each(|(slave, requested, actual)| {
+                    write!(
+                        f,
+                        " Slave[{}] (requested: {}, actual: {})",
+                        slave, requested, actual
+                    )
+                })
+            }
+            SOEMError::FlushTimeout(pending) => {
+                write!(f, "Timed out waiting for {} frames to be sent", pending)
+            }
+            SOEMError::FramesDropped(pending) => {
+                write!(f, "Link was closed before {} frames were sent", pending)
+            }
+            SOEMError::InterfaceNotSpecified => {
+                write!(
+                    f,
+                    "Interface name or MAC address must be specified for each interface"
+                )
+            }
+            SOEMError::AmbiguousInterface(candidates) => {
+                write!(f, "AUTD devices were found on more than one interface:")?;
+                candidates
+                    .iter()
+                    .try_for_each(|candidate| write!(f, " [{}]", candidate))?;
+                write!(f, ". Specify the interface name or a filter")
+            }
+            SOEMError::ProbeTimeout(names) => {
+                write!(
+                    f,
+                    "Probing network interfaces ({}) timed out. Specify the interface name or a filter, or increase the lookup timeout",
+                    names.join(", ")
+                )
+            }
+            SOEMError::AdapterNotFound(mac_address) => {
+                write!(
+                    f,
+                    "No network interface with MAC address {} was found",
+                    mac_address
+                )
+            }
+            SOEMError::PermissionDenied => {
+                write!(
+                    f,
+                    "Permission denied to open a raw socket. Run as root or grant CAP_NET_RAW and CAP_NET_ADMIN to the executable, e.g., `sudo setcap cap_net_raw,cap_net_admin=eip <executable>`"
+                )
+            }
+            SOEMError::InterfaceNotFound(ifname) => {
+                write!(
+                    f,
+                    "Network interface {} was not found. See `EthernetAdapters` for the available interfaces",
+                    ifname
+                )
+            }
+            SOEMError::InterfaceDown(ifname) => {
+                write!(
+                    f,
+                    "Network interface {} is down. Bring it up, e.g., `sudo ip link set {} up`",
+                    ifname, ifname
+                )
+            }
+            SOEMError::NoCarrier(ifname) => {
+                write!(
+                    f,
+                    "No link is detected on network interface {}. Check the cable and the power supply of the devices",
+                    ifname
+                )
+            }
+            SOEMError::CycleMismatch(expected, actual) => {
+                write!(
+                    f,
+                    "All interfaces must have the same cycle (expected: {:?}, actual: {:?})",
+                    expected, actual
+                )
+            }
+            SOEMError::NotForeignSlave(position) => {
+                write!(f, "Slave[{}] is not a non-AUTD slave", position)
+            }
+            SOEMError::PdoSizeMismatch(position, expected, actual) => {
+                write!(
+                    f,
+                    "Process data size of Slave[{}] is {} bytes, but {} bytes are given",
+                    position, expected, actual
+                )
+            }
             SOEMError::Io(err) => write!(f, "{}", err),
         }
     }
 }
 
+impl SOEMError {
+    /// Returns `true` if the error may not occur on a retry, e.g., the devices were not ready yet right after they were powered on.
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            SOEMError::NoDeviceFound
+                | SOEMError::SlaveNotFound(..)
+                | SOEMError::NotResponding(..)
+                | SOEMError::NotReachedRequiredState(..)
+                | SOEMError::SynchronizeFailed(..)
+                | SOEMError::DeviceNotFound(..)
+                | SOEMError::NoCarrier(..)
+        )
+    }
+}
+
 impl std::error::Error for SOEMError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
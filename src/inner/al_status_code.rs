@@ -0,0 +1,192 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+macro_rules! al_status_codes {
+    ($($(#[$meta:meta])* $name:ident = $code:literal => $desc:literal,)*) => {
+        /// The AL status code of a slave defined in ETG.1000.6.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum AlStatusCode {
+            $($(#[$meta])* $name,)*
+            /// Vendor specific code (0x8000 to 0xFFFE).
+            Vendor(u16),
+            /// Code not defined in ETG.1000.6.
+            Unknown(u16),
+        }
+
+        impl AlStatusCode {
+            /// The raw value of the code.
+            pub const fn code(self) -> u16 {
+                match self {
+                    $(Self::$name => $code,)*
+                    Self::Vendor(code) | Self::Unknown(code) => code,
+                }
+            }
+
+            /// The description of the code.
+            pub const fn description(self) -> &'static str {
+                match self {
+                    $(Self::$name => $desc,)*
+                    Self::Vendor(_) => "Vendor specific",
+                    Self::Unknown(_) => "Unknown",
+                }
+            }
+        }
+
+        impl From<u16> for AlStatusCode {
+            fn from(code: u16) -> Self {
+                match code {
+                    $($code => Self::$name,)*
+                    0x8000..=0xFFFE => Self::Vendor(code),
+                    _ => Self::Unknown(code),
+                }
+            }
+        }
+    };
+}
+
+al_status_codes! {
+    /// No error.
+    NoError = 0x0000 => "No error",
+    /// Unspecified error.
+    UnspecifiedError = 0x0001 => "Unspecified error",
+    /// No memory.
+    NoMemory = 0x0002 => "No memory",
+    /// Invalid device setup.
+    InvalidDeviceSetup = 0x0003 => "Invalid device setup",
+    /// Invalid requested state change.
+    InvalidStateChange = 0x0011 => "Invalid requested state change",
+    /// Unknown requested state.
+    UnknownState = 0x0012 => "Unknown requested state",
+    /// Bootstrap not supported.
+    BootstrapNotSupported = 0x0013 => "Bootstrap not supported",
+    /// No valid firmware.
+    NoValidFirmware = 0x0014 => "No valid firmware",
+    /// Invalid mailbox configuration in BOOT state.
+    InvalidBootMailboxConfig = 0x0015 => "Invalid mailbox configuration (BOOT state)",
+    /// Invalid mailbox configuration in PRE-OP state.
+    InvalidMailboxConfig = 0x0016 => "Invalid mailbox configuration (PRE-OP state)",
+    /// Invalid sync manager configuration.
+    InvalidSyncManagerConfig = 0x0017 => "Invalid sync manager configuration",
+    /// No valid inputs available.
+    NoValidInputs = 0x0018 => "No valid inputs available",
+    /// No valid outputs.
+    NoValidOutputs = 0x0019 => "No valid outputs",
+    /// Synchronization error.
+    SynchronizationError = 0x001A => "Synchronization error",
+    /// Sync manager watchdog.
+    SyncManagerWatchdog = 0x001B => "Sync manager watchdog",
+    /// Invalid sync manager types.
+    InvalidSyncManagerTypes = 0x001C => "Invalid sync manager types",
+    /// Invalid output configuration.
+    InvalidOutputConfig = 0x001D => "Invalid output configuration",
+    /// Invalid input configuration.
+    InvalidInputConfig = 0x001E => "Invalid input configuration",
+    /// Invalid watchdog configuration.
+    InvalidWatchdogConfig = 0x001F => "Invalid watchdog configuration",
+    /// Slave needs cold start.
+    NeedsColdStart = 0x0020 => "Slave needs cold start",
+    /// Slave needs INIT.
+    NeedsInit = 0x0021 => "Slave needs INIT",
+    /// Slave needs PRE-OP.
+    NeedsPreOp = 0x0022 => "Slave needs PRE-OP",
+    /// Slave needs SAFE-OP.
+    NeedsSafeOp = 0x0023 => "Slave needs SAFE-OP",
+    /// Invalid input mapping.
+    InvalidInputMapping = 0x0024 => "Invalid input mapping",
+    /// Invalid output mapping.
+    InvalidOutputMapping = 0x0025 => "Invalid output mapping",
+    /// Inconsistent settings.
+    InconsistentSettings = 0x0026 => "Inconsistent settings",
+    /// Free-run not supported.
+    FreeRunNotSupported = 0x0027 => "Free-run not supported",
+    /// Synchronization not supported.
+    SynchronizationNotSupported = 0x0028 => "Synchronization not supported",
+    /// Free-run needs 3-buffer mode.
+    FreeRunNeeds3Buffer = 0x0029 => "Free-run needs 3-buffer mode",
+    /// Background watchdog.
+    BackgroundWatchdog = 0x002A => "Background watchdog",
+    /// No valid inputs and outputs.
+    NoValidInputsAndOutputs = 0x002B => "No valid inputs and outputs",
+    /// Fatal sync error.
+    FatalSyncError = 0x002C => "Fatal sync error",
+    /// No sync error.
+    NoSyncError = 0x002D => "No sync error",
+    /// Invalid DC SYNC configuration.
+    InvalidDcSyncConfig = 0x0030 => "Invalid DC SYNC configuration",
+    /// Invalid DC latch configuration.
+    InvalidDcLatchConfig = 0x0031 => "Invalid DC latch configuration",
+    /// PLL error.
+    PllError = 0x0032 => "PLL error",
+    /// DC sync IO error.
+    DcSyncIoError = 0x0033 => "DC sync IO error",
+    /// DC sync timeout error.
+    DcSyncTimeout = 0x0034 => "DC sync timeout error",
+    /// DC invalid sync cycle time.
+    DcInvalidSyncCycleTime = 0x0035 => "DC invalid sync cycle time",
+    /// DC SYNC0 cycle time.
+    DcSync0CycleTime = 0x0036 => "DC SYNC0 cycle time",
+    /// DC SYNC1 cycle time.
+    DcSync1CycleTime = 0x0037 => "DC SYNC1 cycle time",
+    /// AoE mailbox error.
+    MailboxAoe = 0x0041 => "Mailbox AoE",
+    /// EoE mailbox error.
+    MailboxEoe = 0x0042 => "Mailbox EoE",
+    /// CoE mailbox error.
+    MailboxCoe = 0x0043 => "Mailbox CoE",
+    /// FoE mailbox error.
+    MailboxFoe = 0x0044 => "Mailbox FoE",
+    /// SoE mailbox error.
+    MailboxSoe = 0x0045 => "Mailbox SoE",
+    /// VoE mailbox error.
+    MailboxVoe = 0x004F => "Mailbox VoE",
+    /// EEPROM no access.
+    EepromNoAccess = 0x0050 => "EEPROM no access",
+    /// EEPROM error.
+    EepromError = 0x0051 => "EEPROM error",
+    /// Slave restarted locally.
+    RestartedLocally = 0x0060 => "Slave restarted locally",
+    /// Device identification value updated.
+    DeviceIdentificationUpdated = 0x0061 => "Device identification value updated",
+    /// Application controller available.
+    ApplicationControllerAvailable = 0x00F0 => "Application controller available",
+}
+
+impl std::fmt::Display for AlStatusCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} ({:#06X})", self.description(), self.code())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_al_status_code() {
+        [
+            (AlStatusCode::NoError, 0x0000),
+            (AlStatusCode::SyncManagerWatchdog, 0x001B),
+            (AlStatusCode::ApplicationControllerAvailable, 0x00F0),
+            (AlStatusCode::Vendor(0x8001), 0x8001),
+            (AlStatusCode::Unknown(0x0004), 0x0004),
+            (AlStatusCode::Unknown(0xFFFF), 0xFFFF),
+        ]
+        .into_iter()
+        .for_each(|(expected, code)| {
+            assert_eq!(expected, AlStatusCode::from(code));
+            assert_eq!(code, expected.code());
+        });
+        assert_eq!(
+            "Sync manager watchdog (0x001B)",
+            AlStatusCode::SyncManagerWatchdog.to_string()
+        );
+    }
+}
@@ -58,8 +58,12 @@ impl Context {
         if wc <= 0 { None } else { Some(wc as _) }
     }
 
-    pub fn config_map_group(&self, ptr: *mut c_void) {
-        unsafe { ecx_config_map_group(self.as_mut_ptr(), ptr, 0) };
+    pub fn set_group(&self, idx: usize, group: u8) {
+        self.ctx_mut().slavelist[idx + 1].group = group;
+    }
+
+    pub fn config_map_group(&self, ptr: *mut c_void, group: u8) -> usize {
+        unsafe { ecx_config_map_group(self.as_mut_ptr(), ptr, group) }.max(0) as _
     }
 
     pub fn configdc(&self, userdata: Duration) {
@@ -67,13 +71,17 @@ impl Context {
         unsafe { ecx_configdc(self.as_mut_ptr()) };
     }
 
-    pub fn set_po2so_config(&self) {
-        self.slaves_mut()
-            .for_each(|slave| slave.PO2SOconfig = Some(po2so_config));
+    pub fn set_po2so_config(&self, slaves: &[usize]) {
+        slaves
+            .iter()
+            .for_each(|&idx| self.ctx_mut().slavelist[idx + 1].PO2SOconfig = Some(po2so_config));
     }
 
     pub fn docheckstate(&self) -> bool {
-        self.ctx_mut().grouplist[0].docheckstate != 0
+        self.ctx
+            .grouplist
+            .iter()
+            .any(|group| group.docheckstate != 0)
     }
 
     fn reconfig_slave(&self, slave: u16, timeout: u32) -> i32 {
@@ -96,6 +104,35 @@ impl Context {
         self.ctx_mut().slavelist[idx].state = state.state();
     }
 
+    /// Requests `state` to all slaves and returns the slaves which did not reach it in `timeout` with their actual states.
+    pub fn request_state(&self, state: State, timeout: Duration) -> Vec<(u16, State)> {
+        self.set_state(0, state);
+        self.write_state(0);
+        self.state_check(0, state, timeout.as_micros() as _);
+        self.slaves_not_in(state)
+            .into_iter()
+            .map(|(slave, actual, _)| (slave, actual))
+            .collect()
+    }
+
+    /// Reads the state of all slaves and returns the position, the actual state and the AL status code of the slaves not in `state`.
+    pub fn slaves_not_in(&self, state: State) -> Vec<(u16, State, AlStatusCode)> {
+        self.read_state();
+        self.slaves()
+            .enumerate()
+            .filter_map(|(i, slave)| {
+                let actual = State::from(slave.state);
+                (actual != state).then(|| {
+                    (
+                        (i + 1) as u16,
+                        actual,
+                        AlStatusCode::from(slave.ALstatuscode),
+                    )
+                })
+            })
+            .collect()
+    }
+
     pub fn read_state(&self) {
         unsafe { ecx_readstate(self.as_mut_ptr()) };
     }
@@ -108,7 +145,7 @@ impl Context {
         unsafe {
             ecx_FRMW(
                 self.port(),
-                self.slave(0).configadr,
+                self.reference_clock().configadr,
                 ado,
                 length,
                 data,
@@ -137,26 +174,38 @@ impl Context {
         }
     }
 
-    pub fn send_processdata(&self) {
-        unsafe { ecx_send_processdata(self.as_mut_ptr()) };
+    pub fn send_processdata_group(&self, group: u8) {
+        unsafe { ecx_send_processdata_group(self.as_mut_ptr(), group) };
     }
 
-    pub fn receive_processdata(&self, timeout: i32) -> i32 {
-        unsafe { ecx_receive_processdata(self.as_mut_ptr(), timeout as _) }
+    pub fn receive_processdata_group(&self, group: u8, timeout: i32) -> i32 {
+        unsafe { ecx_receive_processdata_group(self.as_mut_ptr(), group, timeout as _) }
     }
 
     pub fn dctime(&self) -> i64 {
         self.ctx.DCtime
     }
 
-    pub fn expected_wkc(&self) -> i32 {
-        (self.ctx.grouplist[0].outputsWKC * 2 + self.ctx.grouplist[0].inputsWKC) as i32
+    pub fn expected_wkc(&self, group: u8) -> i32 {
+        let group = self.group(group);
+        (group.outputsWKC * 2 + group.inputsWKC) as i32
+    }
+
+    pub fn group(&self, group: u8) -> &ec_groupt {
+        &self.ctx.grouplist[group as usize]
     }
 
     pub fn slave(&self, idx: usize) -> &ec_slavet {
         &self.ctx.slavelist[idx + 1]
     }
 
+    /// The first slave with DC is the reference clock.
+    pub fn reference_clock(&self) -> &ec_slavet {
+        self.slaves()
+            .find(|slave| slave.hasdc != 0)
+            .unwrap_or(self.slave(0))
+    }
+
     pub fn slaves(&self) -> impl std::iter::Iterator<Item = &ec_slavet> {
         let count = self.ctx.slavecount as usize;
         self.ctx.slavelist.iter().skip(1).take(count)
@@ -199,15 +248,18 @@ impl Context {
 
 impl Context {
     pub fn handle_error<F: Fn(u16, Status)>(&self, handler: &F, do_wkc_check: &Arc<AtomicI32>) {
-        self.ctx_mut().grouplist[0].docheckstate = 0;
+        self.ctx_mut()
+            .grouplist
+            .iter_mut()
+            .for_each(|group| group.docheckstate = 0);
         self.read_state();
         self.slaves_mut().enumerate().for_each(|(i, slave)| {
             let slave_idx = (i + 1) as u16;
             let state = State::from(slave.state);
             if state != State::OPERATIONAL {
-                self.ctx_mut().grouplist[0].docheckstate = 1;
+                self.ctx_mut().grouplist[slave.group as usize].docheckstate = 1;
                 if state.is_safe_op() && state.is_error() {
-                    (handler)(slave_idx, Status::Error);
+                    (handler)(slave_idx, Status::Error(slave.ALstatuscode.into()));
                     slave.state = ec_state_EC_STATE_SAFE_OP as u16 + ec_state_EC_STATE_ACK as u16;
                     self.write_state(slave_idx);
                 } else if state.is_safe_op() {
@@ -239,7 +291,7 @@ impl Context {
             }
         });
 
-        if self.ctx.grouplist[0].docheckstate == 0 {
+        if !self.docheckstate() {
             (handler)(0, Status::Resumed);
         }
         do_wkc_check.store(0, Ordering::Relaxed);
@@ -247,10 +299,14 @@ impl Context {
 }
 
 impl Context {
-    pub fn alstatuscode2string(code: u16) -> String {
-        unsafe { std::ffi::CStr::from_ptr(ec_ALstatuscode2string(code)) }
-            .to_string_lossy()
-            .into_owned()
+    /// Pops all errors accumulated in the error list of SOEM.
+    pub fn drain_errors(&self) -> Vec<SlaveError> {
+        let mut errors = Vec::new();
+        let mut err: ec_errort = unsafe { std::mem::zeroed() };
+        while unsafe { ecx_poperror(self.as_mut_ptr(), &mut err) } != 0 {
+            errors.push(SlaveError::from(&err));
+        }
+        errors
     }
 }
 
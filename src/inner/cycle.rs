@@ -0,0 +1,204 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+    time::Duration,
+};
+
+/// Metadata of the process data received by the EtherCAT thread.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RxMetadata {
+    /// The number of the cycle in which the data was received. The first cycle is 1.
+    pub cycle: u64,
+    /// The DC system time of the reference clock in nanoseconds since 2000-01-01.
+    pub dc_time: i64,
+    /// The sum of the working counters of all process data groups. A group whose frame was lost counts as 0.
+    pub wkc: i32,
+    /// Whether the working counters of all process data groups matched the expected values.
+    pub wkc_matched: bool,
+}
+
+struct CycleState {
+    metadata: RxMetadata,
+    stale: Vec<bool>,
+    closed: bool,
+    wakers: Vec<Waker>,
+}
+
+pub struct CycleNotifier {
+    state: Mutex<CycleState>,
+    cond: Condvar,
+}
+
+impl CycleNotifier {
+    pub fn new(num_devices: usize) -> Self {
+        Self {
+            state: Mutex::new(CycleState {
+                metadata: RxMetadata::default(),
+                stale: vec![false; num_devices],
+                closed: false,
+                wakers: Vec::new(),
+            }),
+            cond: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, CycleState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub fn num_devices(&self) -> usize {
+        self.lock().stale.len()
+    }
+
+    pub fn cycle(&self) -> u64 {
+        self.lock().metadata.cycle
+    }
+
+    pub fn metadata(&self) -> RxMetadata {
+        self.lock().metadata
+    }
+
+    pub fn stale_devices(&self) -> Vec<usize> {
+        self.lock()
+            .stale
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &stale)| stale.then_some(i))
+            .collect()
+    }
+
+    pub fn notify(&self, dc_time: i64, wkc: i32, wkc_matched: bool, stale: &[bool]) {
+        let wakers = {
+            let mut state = self.lock();
+            state.stale.copy_from_slice(stale);
+            state.metadata = RxMetadata {
+                cycle: state.metadata.cycle + 1,
+                dc_time,
+                wkc,
+                wkc_matched,
+            };
+            std::mem::take(&mut state.wakers)
+        };
+        self.cond.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    pub fn close(&self) {
+        let wakers = {
+            let mut state = self.lock();
+            state.closed = true;
+            std::mem::take(&mut state.wakers)
+        };
+        self.cond.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Blocks until the cycle counter reaches `target`. Returns `false` if the notifier is closed before that.
+    pub fn wait(&self, target: u64) -> bool {
+        let state = self
+            .cond
+            .wait_while(self.lock(), |state| {
+                state.metadata.cycle < target && !state.closed
+            })
+            .unwrap_or_else(PoisonError::into_inner);
+        state.metadata.cycle >= target
+    }
+
+    /// Same as [`CycleNotifier::wait`], but gives up after `timeout`.
+    pub fn wait_timeout(&self, target: u64, timeout: Duration) -> bool {
+        let (state, _) = self
+            .cond
+            .wait_timeout_while(self.lock(), timeout, |state| {
+                state.metadata.cycle < target && !state.closed
+            })
+            .unwrap_or_else(PoisonError::into_inner);
+        state.metadata.cycle >= target
+    }
+
+    pub fn wait_async(self: &Arc<Self>, target: u64) -> CycleFuture {
+        CycleFuture {
+            notifier: self.clone(),
+            target,
+        }
+    }
+}
+
+pub struct CycleFuture {
+    notifier: Arc<CycleNotifier>,
+    target: u64,
+}
+
+impl Future for CycleFuture {
+    type Output = bool;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.notifier.lock();
+        if state.metadata.cycle >= self.target {
+            return Poll::Ready(true);
+        }
+        if state.closed {
+            return Poll::Ready(false);
+        }
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
+
+/// Closes the notifier when the EtherCAT thread exits, even if it panics.
+pub struct CloseOnDrop(pub Arc<CycleNotifier>);
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        self.0.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_notifier() {
+        let notifier = Arc::new(CycleNotifier::new(2));
+        let th = std::thread::spawn({
+            let notifier = notifier.clone();
+            move || notifier.wait(3)
+        });
+        (0..3).for_each(|i| notifier.notify(i, 3, true, &[false, false]));
+        assert!(th.join().unwrap());
+        assert_eq!(
+            RxMetadata {
+                cycle: 3,
+                dc_time: 2,
+                wkc: 3,
+                wkc_matched: true,
+            },
+            notifier.metadata()
+        );
+
+        assert!(notifier.stale_devices().is_empty());
+        assert!(!notifier.wait_timeout(4, Duration::from_millis(10)));
+
+        notifier.notify(3, 1, false, &[false, true]);
+        assert!(!notifier.metadata().wkc_matched);
+        assert_eq!(vec![1], notifier.stale_devices());
+
+        notifier.close();
+        assert!(!notifier.wait(5));
+        assert!(notifier.wait(4));
+    }
+}
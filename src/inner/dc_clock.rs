@@ -0,0 +1,186 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::{Duration, Instant, SystemTime};
+
+/// The difference between the UNIX epoch and the EtherCAT epoch (2000-01-01).
+const EC_EPOCH_OFFSET: Duration = Duration::from_secs(946_684_800);
+
+/// A mapping between the DC system time of the reference clock and the host clocks.
+#[derive(Debug, Clone, Copy)]
+pub struct DcClockMapping {
+    /// The host monotonic time at which the mapping is evaluated.
+    pub instant: Instant,
+    /// The host UTC time at [`DcClockMapping::instant`].
+    pub system_time: SystemTime,
+    /// The estimated DC system time at [`DcClockMapping::instant`] in nanoseconds since 2000-01-01.
+    pub dc_time: i64,
+    /// The estimated offset of the DC system time from the host UTC time in nanoseconds. A positive value means the reference clock is ahead.
+    pub offset: i64,
+    /// The estimated drift of the reference clock relative to the host monotonic clock in ppm.
+    pub drift_ppm: f64,
+}
+
+impl DcClockMapping {
+    /// Converts the DC system time to the host monotonic time.
+    pub fn dc_to_instant(&self, dc_time: i64) -> Instant {
+        let host = ((dc_time - self.dc_time) as f64 / (1. + self.drift_ppm * 1e-6)) as i64;
+        if host >= 0 {
+            self.instant + Duration::from_nanos(host as _)
+        } else {
+            self.instant - Duration::from_nanos(host.unsigned_abs())
+        }
+    }
+
+    /// Converts the host monotonic time to the DC system time.
+    pub fn instant_to_dc(&self, instant: Instant) -> i64 {
+        let host = if instant >= self.instant {
+            (instant - self.instant).as_nanos() as f64
+        } else {
+            -((self.instant - instant).as_nanos() as f64)
+        };
+        self.dc_time + (host * (1. + self.drift_ppm * 1e-6)) as i64
+    }
+
+    /// Converts the DC system time to the host UTC time.
+    pub fn dc_to_system_time(&self, dc_time: i64) -> SystemTime {
+        from_ec_nanos(dc_time - self.offset)
+    }
+
+    /// Converts the host UTC time to the DC system time.
+    pub fn system_time_to_dc(&self, system_time: SystemTime) -> i64 {
+        to_ec_nanos(system_time) + self.offset
+    }
+}
+
+fn to_ec_nanos(system_time: SystemTime) -> i64 {
+    match system_time.duration_since(SystemTime::UNIX_EPOCH + EC_EPOCH_OFFSET) {
+        Ok(d) => d.as_nanos() as i64,
+        Err(e) => -(e.duration().as_nanos() as i64),
+    }
+}
+
+fn from_ec_nanos(ns: i64) -> SystemTime {
+    let ec_epoch = SystemTime::UNIX_EPOCH + EC_EPOCH_OFFSET;
+    if ns >= 0 {
+        ec_epoch + Duration::from_nanos(ns as _)
+    } else {
+        ec_epoch - Duration::from_nanos(ns.unsigned_abs())
+    }
+}
+
+/// Estimates the offset and the drift of the reference clock from the samples taken every cycle.
+pub struct DcClockEstimator {
+    origin: Instant,
+    dc_origin: Option<i64>,
+    last: Option<(f64, f64)>,
+    window: Option<(f64, f64)>,
+    drift: Option<f64>,
+}
+
+impl DcClockEstimator {
+    const ALPHA: f64 = 0.01;
+    const DRIFT_ALPHA: f64 = 0.2;
+    const WINDOW: f64 = 1e9;
+
+    pub fn new(origin: Instant) -> Self {
+        Self {
+            origin,
+            dc_origin: None,
+            last: None,
+            window: None,
+            drift: None,
+        }
+    }
+
+    pub fn push(&mut self, host: Instant, dc_time: i64) {
+        let t = host.saturating_duration_since(self.origin).as_nanos() as f64;
+        // DC time is handled relative to the first sample to keep the precision of `f64`.
+        let dc_origin = *self.dc_origin.get_or_insert(dc_time);
+        let raw = (dc_time - dc_origin) as f64 - t;
+        let drift = self.drift.unwrap_or(0.);
+        // The offset is predicted with the current drift estimate so that the smoothing does not lag behind the drift.
+        let offset = match self.last {
+            Some((last_t, last_offset)) => {
+                let predicted = last_offset + drift * (t - last_t);
+                predicted + Self::ALPHA * (raw - predicted)
+            }
+            None => raw,
+        };
+        self.last = Some((t, offset));
+
+        match self.window {
+            Some((window_t, window_offset)) if t - window_t >= Self::WINDOW => {
+                let d = (offset - window_offset) / (t - window_t);
+                self.drift = Some(match self.drift {
+                    Some(drift) => drift + Self::DRIFT_ALPHA * (d - drift),
+                    None => d,
+                });
+                self.window = Some((t, offset));
+            }
+            Some(_) => {}
+            None => self.window = Some((t, offset)),
+        }
+    }
+
+    pub fn mapping(&self, instant: Instant, system_time: SystemTime) -> Option<DcClockMapping> {
+        let dc_origin = self.dc_origin?;
+        let (last_t, last_offset) = self.last?;
+        let drift = self.drift.unwrap_or(0.);
+        let t = instant.saturating_duration_since(self.origin).as_nanos() as f64;
+        let dc_time = dc_origin + (t + last_offset + drift * (t - last_t)) as i64;
+        Some(DcClockMapping {
+            instant,
+            system_time,
+            dc_time,
+            offset: dc_time - to_ec_nanos(system_time),
+            drift_ppm: drift * 1e6,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dc_clock_estimator() {
+        const DRIFT_PPM: f64 = 50.;
+        const DC_START: i64 = 800_000_000_000_000_000;
+
+        let origin = Instant::now();
+        let mut estimator = DcClockEstimator::new(origin);
+        assert!(estimator.mapping(origin, SystemTime::now()).is_none());
+
+        let dc = |t: Duration| DC_START + (t.as_nanos() as f64 * (1. + DRIFT_PPM * 1e-6)) as i64;
+        (0..10_000u64)
+            .map(Duration::from_millis)
+            .for_each(|t| estimator.push(origin + t, dc(t)));
+
+        let t = Duration::from_secs(10);
+        let mapping = estimator
+            .mapping(origin + t, SystemTime::UNIX_EPOCH + EC_EPOCH_OFFSET)
+            .unwrap();
+        assert!((mapping.drift_ppm - DRIFT_PPM).abs() < 1.);
+        assert!((mapping.dc_time - dc(t)).abs() < 1_000);
+        assert_eq!(mapping.dc_time, mapping.offset);
+
+        let later = origin + Duration::from_secs(11);
+        assert!((mapping.instant_to_dc(later) - dc(Duration::from_secs(11))).abs() < 1_000);
+        let back = mapping.dc_to_instant(mapping.instant_to_dc(later));
+        assert!(back.max(later) - back.min(later) < Duration::from_micros(1));
+
+        let now = SystemTime::UNIX_EPOCH + EC_EPOCH_OFFSET + Duration::from_secs(1);
+        assert_eq!(
+            now,
+            mapping.dc_to_system_time(mapping.system_time_to_dc(now))
+        );
+    }
+}
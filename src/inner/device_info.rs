@@ -0,0 +1,138 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::ffi::CString;
+
+use autd3_core::link::LinkError;
+
+use super::{
+    Context, ECT_MBXPROT_AOE, ECT_MBXPROT_COE, ECT_MBXPROT_EOE, ECT_MBXPROT_FOE, ECT_MBXPROT_SOE,
+    ECT_MBXPROT_VOE, ec_slave, preflight,
+    utils::{is_autd3, slave_name},
+};
+use crate::error::SOEMError;
+
+/// The mailbox protocols supported by a slave.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MailboxProtocols {
+    /// ADS over EtherCAT.
+    pub aoe: bool,
+    /// Ethernet over EtherCAT.
+    pub eoe: bool,
+    /// CANopen over EtherCAT.
+    pub coe: bool,
+    /// File access over EtherCAT.
+    pub foe: bool,
+    /// Servo drive profile over EtherCAT.
+    pub soe: bool,
+    /// Vendor specific protocol over EtherCAT.
+    pub voe: bool,
+}
+
+impl From<u16> for MailboxProtocols {
+    fn from(proto: u16) -> Self {
+        let proto = proto as u32;
+        Self {
+            aoe: proto & ECT_MBXPROT_AOE != 0,
+            eoe: proto & ECT_MBXPROT_EOE != 0,
+            coe: proto & ECT_MBXPROT_COE != 0,
+            foe: proto & ECT_MBXPROT_FOE != 0,
+            soe: proto & ECT_MBXPROT_SOE != 0,
+            voe: proto & ECT_MBXPROT_VOE != 0,
+        }
+    }
+}
+
+/// The identity of a slave on the bus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeviceInfo {
+    /// The position of the slave on the bus starting from 1, which is also the index passed to the error handler.
+    pub position: u16,
+    /// The index of the device in the geometry. `None` if the slave is not an AUTD device.
+    pub device_index: Option<usize>,
+    /// The name of the slave.
+    pub name: String,
+    /// The vendor ID.
+    pub vendor_id: u32,
+    /// The product code.
+    pub product_code: u32,
+    /// The revision number.
+    pub revision: u32,
+    /// The serial number in the EEPROM.
+    pub serial_number: u32,
+    /// The configured station alias.
+    pub alias: u16,
+    /// The configured station address.
+    pub configured_address: u16,
+    /// The supported mailbox protocols.
+    pub mailbox_protocols: MailboxProtocols,
+    /// Whether the slave supports distributed clocks.
+    pub has_dc: bool,
+}
+
+impl DeviceInfo {
+    pub(crate) fn new(idx: usize, slave: &ec_slave, device_index: Option<usize>) -> Self {
+        Self {
+            position: (idx + 1) as _,
+            device_index,
+            name: slave_name(slave),
+            vendor_id: slave.eep_man,
+            product_code: slave.eep_id,
+            revision: slave.eep_rev,
+            serial_number: slave.eep_ser,
+            alias: slave.aliasadr,
+            configured_address: slave.configadr,
+            mailbox_protocols: slave.mbx_proto.into(),
+            has_dc: slave.hasdc != 0,
+        }
+    }
+
+    /// Scans the slaves on the network interface `ifname` without opening the link.
+    ///
+    /// The AUTD devices are indexed in the order on the bus. Note that the scan resets the slaves to the init state, so this must not be called for the interface used by an opened link.
+    pub fn scan(ifname: &str) -> Result<Vec<DeviceInfo>, LinkError> {
+        preflight::preflight_check(ifname)?;
+        let ifname =
+            CString::new(ifname).map_err(|_| SOEMError::InvalidInterfaceName(ifname.to_owned()))?;
+        let ctx = Context::new();
+        ctx.init(ifname)?;
+        if ctx.config_init().is_none() {
+            return Ok(Vec::new());
+        }
+        let mut device_index = 0;
+        Ok(ctx
+            .slaves()
+            .enumerate()
+            .map(|(i, slave)| {
+                let index = is_autd3(slave).then(|| {
+                    device_index += 1;
+                    device_index - 1
+                });
+                DeviceInfo::new(i, slave, index)
+            })
+            .collect())
+    }
+}
+
+impl std::fmt::Display for DeviceInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Slave[{}] {} (Vendor ID: {:#010X}, Product code: {:#010X}, Revision: {:#010X}, Serial number: {}, Alias: {:#06X})",
+            self.position,
+            self.name,
+            self.vendor_id,
+            self.product_code,
+            self.revision,
+            self.serial_number,
+            self.alias
+        )
+    }
+}
@@ -12,13 +12,144 @@ use crate::inner;
 
 use std::ffi::CStr;
 
+/// A MAC address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MacAddress(pub [u8; 6]);
+
+impl std::str::FromStr for MacAddress {
+    type Err = String;
+
+    /// Parses a MAC address in the form of `aa:bb:cc:dd:ee:ff` or `aa-bb-cc-dd-ee-ff`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.trim()
+            .split([':', '-'])
+            .map(|o| {
+                (o.len() == 2)
+                    .then(|| u8::from_str_radix(o, 16).ok())
+                    .flatten()
+            })
+            .collect::<Option<Vec<_>>>()
+            .and_then(|octets| octets.try_into().ok())
+            .map(Self)
+            .ok_or_else(|| format!("Invalid MAC address: {}", s))
+    }
+}
+
+impl std::fmt::Display for MacAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+/// The operational state of a network interface defined in RFC 2863.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperState {
+    /// The state is unknown.
+    Unknown,
+    /// The interface is not present.
+    NotPresent,
+    /// The interface is down.
+    Down,
+    /// The interface is down due to the state of the lower layer.
+    LowerLayerDown,
+    /// The interface is in test mode.
+    Testing,
+    /// The interface is waiting for an external event.
+    Dormant,
+    /// The interface is up.
+    Up,
+}
+
+impl OperState {
+    #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+    fn parse(s: &str) -> Self {
+        match s {
+            "notpresent" => Self::NotPresent,
+            "down" => Self::Down,
+            "lowerlayerdown" => Self::LowerLayerDown,
+            "testing" => Self::Testing,
+            "dormant" => Self::Dormant,
+            "up" => Self::Up,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// The duplex mode of a link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Duplex {
+    /// Half duplex.
+    Half,
+    /// Full duplex.
+    Full,
+}
+
+/// A network adapter.
 #[derive(Clone, Debug)]
 pub struct EthernetAdapter {
     desc: String,
     name: String,
+    mac_address: Option<MacAddress>,
+    oper_state: Option<OperState>,
+    carrier: Option<bool>,
+    speed: Option<u32>,
+    duplex: Option<Duplex>,
+    mtu: Option<u32>,
+    driver: Option<String>,
 }
 
 impl EthernetAdapter {
+    pub(crate) fn new(name: String, desc: String) -> Self {
+        let adapter = Self {
+            desc,
+            name,
+            mac_address: None,
+            oper_state: None,
+            carrier: None,
+            speed: None,
+            duplex: None,
+            mtu: None,
+            driver: None,
+        };
+        #[cfg(target_os = "linux")]
+        let adapter = {
+            let dir = std::path::Path::new("/sys/class/net").join(&adapter.name);
+            adapter.read_sysfs(&dir)
+        };
+        adapter
+    }
+
+    #[cfg(target_os = "linux")]
+    fn read_sysfs(self, dir: &std::path::Path) -> Self {
+        let read = |attr: &str| {
+            std::fs::read_to_string(dir.join(attr))
+                .ok()
+                .map(|s| s.trim().to_owned())
+        };
+        Self {
+            mac_address: read("address").and_then(|s| s.parse().ok()),
+            oper_state: read("operstate").map(|s| OperState::parse(&s)),
+            // `carrier` and `speed` cannot be read while the interface is down.
+            carrier: read("carrier").map(|s| s == "1"),
+            speed: read("speed").and_then(|s| s.parse().ok()),
+            duplex: read("duplex").and_then(|s| match s.as_str() {
+                "full" => Some(Duplex::Full),
+                "half" => Some(Duplex::Half),
+                _ => None,
+            }),
+            mtu: read("mtu").and_then(|s| s.parse().ok()),
+            driver: std::fs::read_link(dir.join("device/driver"))
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned())),
+            ..self
+        }
+    }
+
     /// The description of the adapter.
     pub fn desc(&self) -> &str {
         &self.desc
@@ -28,6 +159,41 @@ impl EthernetAdapter {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    /// The MAC address of the adapter. Only available on Linux.
+    pub fn mac_address(&self) -> Option<MacAddress> {
+        self.mac_address
+    }
+
+    /// The operational state of the adapter. Only available on Linux.
+    pub fn oper_state(&self) -> Option<OperState> {
+        self.oper_state
+    }
+
+    /// Whether the link is detected. Only available on Linux, and `None` while the adapter is down.
+    pub fn carrier(&self) -> Option<bool> {
+        self.carrier
+    }
+
+    /// The link speed in Mbps. Only available on Linux, and `None` if the link is not established.
+    pub fn speed(&self) -> Option<u32> {
+        self.speed
+    }
+
+    /// The duplex mode of the link. Only available on Linux, and `None` if the link is not established.
+    pub fn duplex(&self) -> Option<Duplex> {
+        self.duplex
+    }
+
+    /// The MTU of the adapter. Only available on Linux.
+    pub fn mtu(&self) -> Option<u32> {
+        self.mtu
+    }
+
+    /// The name of the kernel driver of the adapter. Only available on Linux, and `None` for virtual adapters.
+    pub fn driver(&self) -> Option<&str> {
+        self.driver.as_deref()
+    }
 }
 
 impl std::fmt::Display for EthernetAdapter {
@@ -68,13 +234,13 @@ impl EthernetAdapters {
             let mut adapter = head;
             while !adapter.is_null() {
                 if let Ok(name) = CStr::from_ptr(((*adapter).name).as_ptr()).to_str() {
-                    adapters.push(EthernetAdapter {
-                        desc: CStr::from_ptr(((*adapter).desc).as_ptr())
+                    adapters.push(EthernetAdapter::new(
+                        name.to_string(),
+                        CStr::from_ptr(((*adapter).desc).as_ptr())
                             .to_str()
                             .unwrap_or("")
                             .to_string(),
-                        name: name.to_string(),
-                    });
+                    ));
                 }
                 adapter = (*adapter).next;
             }
@@ -89,3 +255,62 @@ impl Default for EthernetAdapters {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mac_address() {
+        let addr = MacAddress([0x00, 0x1b, 0x21, 0xaa, 0xbb, 0xcc]);
+        assert_eq!(Ok(addr), "00:1b:21:aa:bb:cc".parse());
+        assert_eq!(Ok(addr), "00-1B-21-AA-BB-CC".parse());
+        assert_eq!("00:1b:21:aa:bb:cc", addr.to_string());
+        assert!("00:1b:21:aa:bb".parse::<MacAddress>().is_err());
+        assert!("00:1b:21:aa:bb:cc:dd".parse::<MacAddress>().is_err());
+        assert!("00:1b:21:aa:bb:c".parse::<MacAddress>().is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_read_sysfs() {
+        let dir =
+            std::env::temp_dir().join(format!("autd3-link-soem-sysfs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        [
+            ("address", "00:1b:21:aa:bb:cc\n"),
+            ("operstate", "up\n"),
+            ("carrier", "1\n"),
+            ("speed", "100\n"),
+            ("duplex", "full\n"),
+            ("mtu", "1500\n"),
+        ]
+        .into_iter()
+        .for_each(|(attr, value)| std::fs::write(dir.join(attr), value).unwrap());
+
+        let adapter = EthernetAdapter {
+            desc: String::new(),
+            name: "eth0".to_owned(),
+            mac_address: None,
+            oper_state: None,
+            carrier: None,
+            speed: None,
+            duplex: None,
+            mtu: None,
+            driver: None,
+        }
+        .read_sysfs(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            Some(MacAddress([0x00, 0x1b, 0x21, 0xaa, 0xbb, 0xcc])),
+            adapter.mac_address()
+        );
+        assert_eq!(Some(OperState::Up), adapter.oper_state());
+        assert_eq!(Some(true), adapter.carrier());
+        assert_eq!(Some(100), adapter.speed());
+        assert_eq!(Some(Duplex::Full), adapter.duplex());
+        assert_eq!(Some(1500), adapter.mtu());
+        assert_eq!(None, adapter.driver());
+    }
+}
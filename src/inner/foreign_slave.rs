@@ -0,0 +1,105 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use super::{ec_slave, utils::slave_name};
+
+/// A slave on the bus other than AUTD devices.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForeignSlave {
+    /// The position of the slave on the bus starting from 1, which is also the index passed to the error handler.
+    pub position: u16,
+    /// The name of the slave.
+    pub name: String,
+    /// The vendor ID.
+    pub vendor_id: u32,
+    /// The product code.
+    pub product_code: u32,
+    /// The size of the output process data in bits.
+    pub output_bits: usize,
+    /// The bit position of the output process data in the first byte. This is not 0 only if the outputs are less than 8 bits, which SOEM packs with the outputs of other slaves.
+    pub output_start_bit: u8,
+    /// The number of bytes which contain the output process data.
+    pub output_bytes: usize,
+    /// The size of the input process data in bits.
+    pub input_bits: usize,
+    /// The bit position of the input process data in the first byte. This is not 0 only if the inputs are less than 8 bits, which SOEM packs with the inputs of other slaves.
+    pub input_start_bit: u8,
+    /// The number of bytes which contain the input process data.
+    pub input_bytes: usize,
+}
+
+impl ForeignSlave {
+    pub(crate) fn new(idx: usize, slave: &ec_slave) -> Self {
+        Self {
+            position: (idx + 1) as _,
+            name: slave_name(slave),
+            vendor_id: slave.eep_man,
+            product_code: slave.eep_id,
+            output_bits: slave.Obits as _,
+            output_start_bit: slave.Ostartbit,
+            output_bytes: covering_bytes(slave.Ostartbit, slave.Obits) as _,
+            input_bits: slave.Ibits as _,
+            input_start_bit: slave.Istartbit,
+            input_bytes: covering_bytes(slave.Istartbit, slave.Ibits) as _,
+        }
+    }
+}
+
+/// Returns the number of bytes which contain `bits` bits starting from `start_bit`.
+///
+/// SOEM sets `Obytes` and `Ibytes` to 0 for the process data less than 8 bits, so they cannot be used to access such data.
+pub(crate) fn covering_bytes(start_bit: u8, bits: u16) -> u32 {
+    (start_bit as u32 + bits as u32).div_ceil(8)
+}
+
+/// Copies `bits` bits starting from `start_bit` from `src` to `dst`, keeping the other bits of `dst`, which may belong to other slaves.
+pub(crate) fn copy_bits(dst: &mut [u8], src: &[u8], start_bit: u8, bits: u16) {
+    let start = start_bit as usize;
+    let end = start + bits as usize;
+    dst.iter_mut()
+        .zip(src.iter())
+        .enumerate()
+        .for_each(|(i, (dst, &src))| {
+            let lo = start.clamp(i * 8, i * 8 + 8) - i * 8;
+            let hi = end.clamp(i * 8, i * 8 + 8) - i * 8;
+            let mask = ((0xFFu16 << lo) & !(0xFFu16 << hi)) as u8;
+            *dst = (*dst & !mask) | (src & mask);
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_covering_bytes() {
+        assert_eq!(0, covering_bytes(0, 0));
+        assert_eq!(1, covering_bytes(0, 1));
+        assert_eq!(1, covering_bytes(4, 4));
+        assert_eq!(2, covering_bytes(6, 4));
+        assert_eq!(1, covering_bytes(0, 8));
+        assert_eq!(3, covering_bytes(0, 24));
+    }
+
+    #[test]
+    fn test_copy_bits() {
+        let mut dst = [0b1010_1010];
+        copy_bits(&mut dst, &[0b0101_0101], 2, 3);
+        assert_eq!([0b1011_0110], dst);
+
+        let mut dst = [0xFF, 0xFF];
+        copy_bits(&mut dst, &[0x00, 0x00], 6, 4);
+        assert_eq!([0x3F, 0xFC], dst);
+
+        let mut dst = [0x00, 0x00];
+        copy_bits(&mut dst, &[0x12, 0x34], 0, 16);
+        assert_eq!([0x12, 0x34], dst);
+    }
+}
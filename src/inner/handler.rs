@@ -10,34 +10,56 @@
 
 use std::sync::mpsc::{Receiver, RecvError, SendError, SyncSender as Sender, sync_channel};
 use std::{
+    num::NonZeroUsize,
     sync::{
-        Arc, Mutex,
-        atomic::{AtomicBool, AtomicI32, Ordering},
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicI32, AtomicUsize, Ordering},
     },
     thread::JoinHandle,
-    time::Duration,
+    time::{Duration, Instant, SystemTime},
 };
 
-use time::ext::NumericalDuration;
-
 use autd3_core::{
-    geometry::Geometry,
     link::{RxMessage, TxMessage},
     sleep::Sleeper,
 };
 
-use crate::{error::SOEMError, inner::option::SOEMOptionFull};
+use crate::{
+    error::SOEMError,
+    inner::option::{DeadlineMissAction, DeadlineMissCounter, DeadlineMissPolicy, SOEMOptionFull},
+    sleeper::CycleSleeper,
+};
 
 use super::{
-    Context, State, Status, consts::*, iomap::IOMap, smoothing::Smoothing, utils::is_autd3,
+    Context, DcClockMapping, DeviceId, DeviceInfo, ForeignSlave, RxMetadata, State, Status,
+    consts::*,
+    cycle::{CloseOnDrop, CycleFuture, CycleNotifier},
+    dc_clock::DcClockEstimator,
+    foreign_slave::{copy_bits, covering_bytes},
+    iomap::IOMap,
+    open_report::{OpenReport, OpenStage, SlaveReport, StageTimer},
+    preflight,
+    smoothing::Smoothing,
+    utils::is_autd3,
 };
 
 pub struct SOEMHandler {
     ctx: Arc<Context>,
     send_queue: Sender<Vec<TxMessage>>,
+    urgent_queue: Sender<(Vec<TxMessage>, bool)>,
     buffer_queue: Receiver<Vec<TxMessage>>,
     is_open: Arc<AtomicBool>,
     io_map: Arc<Mutex<IOMap>>,
+    notifier: Arc<CycleNotifier>,
+    dc_clock: Arc<Mutex<DcClockEstimator>>,
+    reject_stale_input: bool,
+    device_slaves: Vec<usize>,
+    foreign_slaves: Vec<usize>,
+    open_report: OpenReport,
+    shutdown_timeout: Duration,
+    flush_on_close: Option<Duration>,
+    pending: Arc<AtomicUsize>,
+    check_running: Arc<AtomicBool>,
     ecat_th: Option<JoinHandle<Result<(), SOEMError>>>,
     ecat_check_th: Option<JoinHandle<()>>,
 }
@@ -49,109 +71,270 @@ impl SOEMHandler {
     >(
         err_handler: F,
         option: SOEMOptionFull,
-        geometry: &Geometry,
-        sleeper: S,
+        expected_devices: Option<usize>,
+        sleeper: CycleSleeper<S>,
     ) -> Result<Self, SOEMError> {
         tracing::debug!("Opening SOEM link: {:?}", option);
 
+        let err_handler = Arc::new(err_handler);
+
         option.validate()?;
-        let ifname = option.ifname()?;
+
+        let policy = option.retry_policy;
+        let sleeper = Arc::new(Mutex::new(Some(sleeper)));
+        let mut attempt = 1;
+        loop {
+            let mut option = option.clone();
+            if let Some(sync_timeout) = policy.sync_timeout {
+                option.sync_timeout = sync_timeout;
+            }
+            let err = match Self::open_once(
+                err_handler.clone(),
+                option,
+                expected_devices,
+                sleeper.clone(),
+            ) {
+                Ok(handler) => return Ok(handler),
+                Err(err) => err,
+            };
+            // The sleeper is lost if the EtherCAT thread panicked.
+            let retry = attempt < policy.max_attempts.get()
+                && err.is_transient()
+                && sleeper
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .is_some();
+            tracing::warn!(
+                "Attempt {}/{} to open SOEM link failed: {}",
+                attempt,
+                policy.max_attempts,
+                err
+            );
+            (err_handler)(
+                0,
+                Status::OpenFailed {
+                    attempt,
+                    error: err.to_string(),
+                    retry,
+                },
+            );
+            if !retry {
+                return Err(err);
+            }
+            let backoff = policy.backoff(attempt as u32 - 1);
+            tracing::info!("Retrying to open SOEM link in {:?}.", backoff);
+            std::thread::sleep(backoff);
+            attempt += 1;
+        }
+    }
+
+    fn open_once<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Send + 'static>(
+        err_handler: Arc<F>,
+        option: SOEMOptionFull,
+        expected_devices: Option<usize>,
+        sleeper: Arc<Mutex<Option<CycleSleeper<S>>>>,
+    ) -> Result<Self, SOEMError> {
+        let ifname = option.ifname(expected_devices)?;
+        let ifname_str = ifname.to_string_lossy().into_owned();
+        preflight::preflight_check(&ifname_str)?;
 
         let ctx = Arc::new(Context::new());
 
+        let mut timer = StageTimer::new();
         tracing::info!("Initializing SOEM with interface {:?}.", ifname);
         ctx.init(ifname)?;
+        timer.finish(OpenStage::Init);
 
-        let num_devices = if let Some(wc) = ctx.config_init() {
-            if !geometry.is_empty() && wc != geometry.len() {
-                return Err(SOEMError::SlaveNotFound(wc as _, geometry.len() as _));
-            }
-            wc
-        } else {
-            return Err(SOEMError::SlaveNotFound(0, geometry.len() as _));
+        let Some(wc) = ctx.config_init() else {
+            return Err(SOEMError::SlaveNotFound(
+                0,
+                expected_devices.unwrap_or(0) as _,
+            ));
         };
-        tracing::info!(
-            "Found {} slave{}.",
-            num_devices,
-            if num_devices > 1 { "s" } else { "" }
-        );
+        tracing::info!("Found {} slave{}.", wc, if wc > 1 { "s" } else { "" });
 
-        ctx.slaves().enumerate().try_for_each(|(i, slave)| {
-            if is_autd3(slave) {
+        let (mut device_slaves, foreign_slaves): (Vec<_>, Vec<_>) =
+            (0..wc).partition(|&i| is_autd3(ctx.slave(i)));
+        foreign_slaves.iter().try_for_each(|&i| {
+            if option.allow_foreign_slaves {
+                tracing::info!(
+                    "Slave[{}] ({}) is not an AUTD device.",
+                    i + 1,
+                    ForeignSlave::new(i, ctx.slave(i)).name
+                );
                 Ok(())
             } else {
                 tracing::error!("Slave[{}] is not an AUTD device.", i + 1);
                 Err(SOEMError::NoDeviceFound)
             }
         })?;
+        if device_slaves.is_empty() {
+            return Err(SOEMError::NoDeviceFound);
+        }
+        let num_devices = device_slaves.len();
+        if let Some(expected) = expected_devices
+            && num_devices != expected
+        {
+            return Err(SOEMError::SlaveNotFound(num_devices as _, expected as _));
+        }
+        if let Some(ids) = &option.device_ids {
+            device_slaves = map_devices(&ctx, &device_slaves, ids)?;
+        }
+
+        let mut groups = assign_groups(
+            &ctx,
+            &device_slaves,
+            &foreign_slaves,
+            option.device_groups.as_deref(),
+        )?;
+        timer.finish(OpenStage::ConfigInit);
 
         tracing::info!(
             "Configuring Sync0 with cycle time {:?}.",
             option.sync0_cycle
         );
         ctx.configdc(option.sync0_cycle);
-        ctx.set_po2so_config();
+        ctx.set_po2so_config(&device_slaves);
+        timer.finish(OpenStage::ConfigDc);
 
-        wait_for_sync(
-            &ctx,
-            num_devices,
-            option.sync_tolerance,
-            option.sync_timeout,
-        )?;
+        let dc_diffs = wait_for_sync(&ctx, option.sync_tolerance, option.sync_timeout)?;
+        timer.finish(OpenStage::Synchronize);
 
-        let io_map = Arc::new(Mutex::new(IOMap::new(num_devices)));
-        ctx.config_map_group(io_map.lock().unwrap().as_ptr() as _);
+        let iomap_size = groups.iter().map(ProcessDataGroup::iomap_size).sum();
+        let io_map = Arc::new(Mutex::new(IOMap::with_size(iomap_size, num_devices)));
+        // SOEM exchanges the process data through this buffer, which is owned by the EtherCAT thread.
+        let mut work = vec![0x00; iomap_size];
+        {
+            let mut io_map = io_map.lock().unwrap();
+            // Each group is mapped to its own region of the IOMap.
+            groups.iter_mut().try_fold(0, |offset, group| {
+                let capacity = group.iomap_size();
+                let size = ctx.config_map_group(work[offset..].as_mut_ptr() as _, group.group);
+                if size > capacity {
+                    return Err(SOEMError::IOMapOverflow(size, capacity));
+                }
+                group.expected_wkc = ctx.expected_wkc(group.group);
+                tracing::debug!(
+                    "Group {} is mapped to {} bytes of the IOMap (expected WKC: {}).",
+                    group.group,
+                    size,
+                    group.expected_wkc
+                );
+                Ok(offset + capacity)
+            })?;
+            let base = work.as_ptr();
+            io_map.remap(
+                base,
+                device_slaves.iter().map(|&i| {
+                    let slave = ctx.slave(i);
+                    (slave.outputs as *const u8, slave.inputs as *const u8)
+                }),
+            );
+            io_map.map_groups(
+                base,
+                groups.iter().map(|group| {
+                    let group = ctx.group(group.group);
+                    (
+                        (group.outputs as *const u8, group.Obytes),
+                        (group.inputs as *const u8, group.Ibytes),
+                    )
+                }),
+            );
+            io_map.map_foreign_slaves(
+                base,
+                foreign_slaves.iter().map(|&i| {
+                    let slave = ctx.slave(i);
+                    (
+                        (
+                            slave.outputs as *const u8,
+                            covering_bytes(slave.Ostartbit, slave.Obits),
+                        ),
+                        (
+                            slave.inputs as *const u8,
+                            covering_bytes(slave.Istartbit, slave.Ibits),
+                        ),
+                    )
+                }),
+            );
+        }
+        timer.finish(OpenStage::MapProcessData);
 
         tracing::info!("Checking if all devices are in safe operational state.");
         let reqstate = State::SAFE_OP;
         ctx.state_check(0, reqstate, EC_TIMEOUTSTATE * 3);
-        let state = ctx.fetch_state(0);
-        if state != reqstate {
-            return Err(SOEMError::NotReachedRequiredState(reqstate, state));
+        if ctx.fetch_state(0) != reqstate {
+            let slaves = ctx.slaves_not_in(reqstate);
+            slaves.iter().for_each(|(slave, state, code)| {
+                tracing::error!(
+                    "Slave[{}] did not reach {} state (State={}, StatusCode={})",
+                    slave,
+                    reqstate,
+                    state,
+                    code
+                );
+            });
+            return Err(SOEMError::NotReachedRequiredState(reqstate, slaves));
         }
         tracing::info!("All devices are in safe operational state.");
+        timer.finish(OpenStage::SafeOp);
 
         let is_open = Arc::new(AtomicBool::new(true));
+        let notifier = Arc::new(CycleNotifier::new(num_devices));
+        let dc_clock = Arc::new(Mutex::new(DcClockEstimator::new(Instant::now())));
         let do_wkc_check = Arc::new(AtomicI32::new(0));
+        let pending = Arc::new(AtomicUsize::new(0));
 
         let state_check_interval = option.state_check_interval;
         let buf_size = option.buf_size.get();
         let (send_queue_sender, send_queue_receiver) = sync_channel(buf_size);
+        let (urgent_queue_sender, urgent_queue_receiver) = sync_channel(buf_size);
         let (buffer_queue_sender, buffer_queue_receiver) = sync_channel(buf_size);
         (0..buf_size).for_each(|_| {
             buffer_queue_sender
                 .send(vec![TxMessage::new(); num_devices])
                 .unwrap()
         });
-        let ecat_th = Some({
+        let Some(cycle_sleeper) = sleeper
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+        else {
+            unreachable!("the sleeper is returned when the EtherCAT thread exits");
+        };
+        let mut ecat_th = Some({
             option.thread_builder.spawn({
                 let is_open = is_open.clone();
                 let io_map = io_map.clone();
-                let expected_wkc = ctx.expected_wkc();
+                let notifier = notifier.clone();
+                let dc_clock = dc_clock.clone();
+                let err_handler = err_handler.clone();
                 let do_wkc_check = do_wkc_check.clone();
+                let pending = pending.clone();
                 let ctx = ctx.clone();
                 move |_| {
-                    if let Some(affinity) = option.affinity {
-                        tracing::info!(
-                            "Setting CPU affinity for the EtherCAT thread to {:?}",
-                            affinity
-                        );
-                        if !core_affinity::set_for_current(affinity) {
-                            tracing::error!("Failed to set CPU affinity for the EtherCAT thread.");
-                            return Err(SOEMError::AffinitySetFailed(affinity));
-                        }
-                    }
-                    ecat_run::<S>(
-                        ctx,
-                        is_open,
-                        io_map,
-                        expected_wkc,
-                        do_wkc_check,
-                        buffer_queue_sender,
-                        send_queue_receiver,
-                        sleeper,
-                        option.send_cycle,
-                    )
+                    let result = set_affinity(option.affinity).and_then(|()| {
+                        ecat_run(
+                            ctx,
+                            is_open,
+                            io_map,
+                            work,
+                            notifier,
+                            dc_clock,
+                            num_devices,
+                            groups,
+                            do_wkc_check,
+                            pending,
+                            buffer_queue_sender,
+                            send_queue_receiver,
+                            urgent_queue_receiver,
+                            &cycle_sleeper,
+                            option.send_cycle,
+                            option.deadline_miss_policy,
+                            err_handler,
+                        )
+                    });
+                    *sleeper.lock().unwrap_or_else(PoisonError::into_inner) = Some(cycle_sleeper);
+                    result
                 }
             })?
         });
@@ -163,29 +346,62 @@ impl SOEMHandler {
         tracing::info!("All devices are in operational state.");
         let state = ctx.fetch_state(0);
         if state != State::OPERATIONAL {
-            ctx.slaves().for_each(|slave| {
+            let slaves = ctx.slaves_not_in(State::OPERATIONAL);
+            slaves.iter().for_each(|(slave, state, code)| {
                 tracing::error!(
-                    "{} (State={}, StatusCode={:#04X})",
-                    Context::alstatuscode2string(slave.ALstatuscode),
-                    State::from(slave.state),
-                    slave.ALstatuscode
+                    "Slave[{}] is not responding (State={}, StatusCode={})",
+                    slave,
+                    state,
+                    code
                 );
             });
-            return Err(SOEMError::NotResponding);
+            is_open.store(false, Ordering::Release);
+            // If the EtherCAT thread failed by itself (e.g., failed to set the affinity), the slaves could not reach OP because of it.
+            if let Some(Ok(Err(e))) = ecat_th.take().map(|th| th.join()) {
+                return Err(e);
+            }
+            return Err(SOEMError::NotResponding(slaves));
         }
+        timer.finish(OpenStage::Operational);
+
+        ctx.read_state();
+        let open_report = OpenReport {
+            ifname: ifname_str,
+            slaves: ctx
+                .slaves()
+                .enumerate()
+                .map(|(i, slave)| SlaveReport {
+                    info: DeviceInfo::new(i, slave, device_slaves.iter().position(|&s| s == i)),
+                    state: State::from(slave.state),
+                    dc_diff: dc_diffs
+                        .iter()
+                        .find_map(|&(j, diff)| (i == j).then_some(diff)),
+                    al_status_code: slave.ALstatuscode.into(),
+                })
+                .collect(),
+            max_dc_diff: max_dc_diff(&dc_diffs),
+            stages: timer.into_stages(),
+        };
+        tracing::info!("Opened in {:?}.", open_report.total());
 
         tracing::info!(
             "Starting EtherCAT state check thread with interval {:?}.",
             state_check_interval
         );
+        let check_running = Arc::new(AtomicBool::new(true));
         let ecat_check_th = Some(std::thread::spawn({
             let is_open = is_open.clone();
+            let check_running = check_running.clone();
             let ctx = ctx.clone();
             move || {
-                while is_open.load(Ordering::Acquire) {
+                while is_open.load(Ordering::Acquire) && check_running.load(Ordering::Acquire) {
                     if do_wkc_check.load(Ordering::Relaxed) > 2 || ctx.docheckstate() {
-                        ctx.handle_error(&err_handler, &do_wkc_check);
+                        ctx.handle_error(&*err_handler, &do_wkc_check);
                     }
+                    ctx.drain_errors().into_iter().for_each(|err| {
+                        tracing::warn!("{}", err);
+                        (err_handler)(err.slave, Status::SlaveError(err));
+                    });
                     std::thread::sleep(state_check_interval);
                 }
             }
@@ -194,59 +410,362 @@ impl SOEMHandler {
         Ok(Self {
             ctx,
             send_queue: send_queue_sender,
+            urgent_queue: urgent_queue_sender,
             buffer_queue: buffer_queue_receiver,
             is_open,
             io_map,
+            notifier,
+            dc_clock,
+            reject_stale_input: option.reject_stale_input,
+            device_slaves,
+            foreign_slaves,
+            open_report,
+            shutdown_timeout: option.shutdown_timeout,
+            flush_on_close: option.flush_on_close,
+            pending,
+            check_running,
             ecat_th,
             ecat_check_th,
         })
     }
 
-    pub fn close(&mut self) {
-        if !self.is_open.load(Ordering::Acquire) {
-            return;
-        }
-        self.is_open.store(false, Ordering::Release);
+    /// Shuts down the slaves in the order of OP, SAFE_OP, PRE_OP and INIT, and closes the link.
+    ///
+    /// If `shutdown_frame` is given, it is sent before the shutdown.
+    pub fn close(&mut self, shutdown_frame: Option<&[TxMessage]>) -> Result<(), SOEMError> {
+        let Some(ecat_th) = self.ecat_th.take() else {
+            return Ok(());
+        };
 
-        if let Some(handle) = self.ecat_th.take() {
-            let _ = handle.join();
+        let flushed = match self.flush_on_close {
+            Some(timeout) if self.is_open() => {
+                tracing::info!("Waiting for the queued frames to be sent.");
+                self.flush(Some(timeout))
+                    .inspect_err(|e| tracing::warn!("{}", e))
+            }
+            _ => Ok(()),
+        };
+
+        if let Some(frame) = shutdown_frame
+            && self.is_open()
+        {
+            tracing::info!("Sending the shutdown frame.");
+            if frame.len() != self.num_devices() {
+                tracing::warn!(
+                    "The shutdown frame is not sent because its length ({}) does not match the number of devices ({}).",
+                    frame.len(),
+                    self.num_devices()
+                );
+            } else if let Ok(mut tx) = self.alloc_tx_buffer() {
+                tx.clone_from_slice(frame);
+                // The frame is put on the wire in the next cycle.
+                if self.send_urgent(tx, true).is_ok() {
+                    self.wait_cycles(NonZeroUsize::new(2).unwrap());
+                }
+            }
         }
+
+        // The state check thread is stopped first so that it does not bring the slaves back to OP.
+        self.check_running.store(false, Ordering::Release);
         if let Some(handle) = self.ecat_check_th.take() {
             let _ = handle.join();
         }
+
+        let mut failed = Vec::new();
+        // The process data is still exchanged during OP -> SAFE_OP so that the watchdog of the slaves does not expire.
+        if self.is_open() {
+            failed.extend(self.request_state(State::SAFE_OP));
+        }
+
+        // The EtherCAT thread may have already stopped by itself, so the thread is joined regardless of `is_open`.
+        self.is_open.store(false, Ordering::Release);
+        let _ = ecat_th.join();
+
+        failed.extend(self.request_state(State::PRE_OP));
+        failed.extend(self.request_state(State::INIT));
         self.ctx.close();
+
+        if failed.is_empty() {
+            flushed
+        } else {
+            Err(SOEMError::ShutdownFailed(failed))
+        }
+    }
+
+    fn request_state(&self, state: State) -> Vec<(u16, State, State)> {
+        tracing::info!("Setting all slaves to {} state.", state);
+        self.ctx
+            .request_state(state, self.shutdown_timeout)
+            .into_iter()
+            .map(|(slave, actual)| {
+                tracing::warn!(
+                    "Slave[{}] did not reach {} state (State={}).",
+                    slave,
+                    state,
+                    actual
+                );
+                (slave, state, actual)
+            })
+            .collect()
     }
 
     pub fn is_open(&self) -> bool {
         self.is_open.load(Ordering::Acquire)
     }
 
+    pub fn num_slaves(&self) -> usize {
+        self.ctx.slaves().count()
+    }
+
+    pub fn num_devices(&self) -> usize {
+        self.notifier.num_devices()
+    }
+
     pub fn alloc_tx_buffer(&mut self) -> Result<Vec<TxMessage>, RecvError> {
         self.buffer_queue.recv()
     }
 
     pub fn send(&mut self, tx: Vec<TxMessage>) -> Result<(), SendError<Vec<TxMessage>>> {
-        self.send_queue.send(tx)
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        self.send_queue.send(tx).inspect_err(|_| {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+        })
+    }
+
+    pub fn send_urgent(
+        &mut self,
+        tx: Vec<TxMessage>,
+        flush: bool,
+    ) -> Result<(), SendError<(Vec<TxMessage>, bool)>> {
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        self.urgent_queue.send((tx, flush)).inspect_err(|_| {
+            self.pending.fetch_sub(1, Ordering::AcqRel);
+        })
+    }
+
+    /// Blocks until all frames sent so far have been put on the wire, or `timeout` elapses.
+    pub fn flush(&self, timeout: Option<Duration>) -> Result<(), SOEMError> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let pending = self.pending.load(Ordering::Acquire);
+            if pending == 0 {
+                return Ok(());
+            }
+            let target = self.notifier.cycle() + 1;
+            let reached = match deadline {
+                Some(deadline) => {
+                    let now = Instant::now();
+                    if now >= deadline {
+                        return Err(SOEMError::FlushTimeout(pending));
+                    }
+                    self.notifier.wait_timeout(target, deadline - now)
+                }
+                None => self.notifier.wait(target),
+            };
+            if !reached && !self.is_open() {
+                return Err(SOEMError::FramesDropped(
+                    self.pending.load(Ordering::Acquire),
+                ));
+            }
+        }
     }
 
     pub fn receive(
         &mut self,
         rx: &mut [RxMessage],
-    ) -> Result<(), std::sync::PoisonError<std::sync::MutexGuard<'_, IOMap>>> {
+    ) -> Result<(RxMetadata, Vec<usize>), std::sync::PoisonError<std::sync::MutexGuard<'_, IOMap>>>
+    {
         let io_map = self.io_map.lock()?;
-        rx.copy_from_slice(io_map.input());
+        io_map.copy_to(rx);
+        Ok((self.notifier.metadata(), self.notifier.stale_devices()))
+    }
+
+    pub fn dc_clock(&self) -> Option<DcClockMapping> {
+        self.dc_clock
+            .lock()
+            .ok()?
+            .mapping(Instant::now(), SystemTime::now())
+    }
+
+    pub fn validate_inputs(&self, stale: Vec<usize>) -> Result<(), SOEMError> {
+        if self.reject_stale_input && !stale.is_empty() {
+            Err(SOEMError::StaleInput(stale))
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn open_report(&self) -> &OpenReport {
+        &self.open_report
+    }
+
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        self.ctx
+            .slaves()
+            .enumerate()
+            .map(|(i, slave)| {
+                DeviceInfo::new(i, slave, self.device_slaves.iter().position(|&s| s == i))
+            })
+            .collect()
+    }
+
+    pub fn foreign_slaves(&self) -> Vec<ForeignSlave> {
+        self.foreign_slaves
+            .iter()
+            .map(|&i| ForeignSlave::new(i, self.ctx.slave(i)))
+            .collect()
+    }
+
+    /// Returns the index in the foreign slaves of the slave at `position`.
+    fn foreign_slave(&self, position: u16) -> Result<usize, SOEMError> {
+        (position as usize)
+            .checked_sub(1)
+            .and_then(|idx| self.foreign_slaves.iter().position(|&i| i == idx))
+            .ok_or(SOEMError::NotForeignSlave(position))
+    }
+
+    pub fn read_foreign_inputs(&self, position: u16) -> Result<Vec<u8>, SOEMError> {
+        let idx = self.foreign_slave(position)?;
+        let io_map = self.io_map.lock().unwrap_or_else(PoisonError::into_inner);
+        Ok(io_map.foreign_inputs(idx).to_vec())
+    }
+
+    pub fn write_foreign_outputs(&self, position: u16, data: &[u8]) -> Result<(), SOEMError> {
+        let idx = self.foreign_slave(position)?;
+        let mut io_map = self.io_map.lock().unwrap_or_else(PoisonError::into_inner);
+        let outputs = io_map.foreign_outputs_mut(idx);
+        if data.len() != outputs.len() {
+            return Err(SOEMError::PdoSizeMismatch(
+                position,
+                outputs.len(),
+                data.len(),
+            ));
+        }
+        let slave = self.ctx.slave(self.foreign_slaves[idx]);
+        copy_bits(outputs, data, slave.Ostartbit, slave.Obits);
         Ok(())
     }
+
+    pub fn wait_cycles(&self, cycles: NonZeroUsize) -> bool {
+        self.notifier
+            .wait(self.notifier.cycle() + cycles.get() as u64)
+    }
+
+    pub fn wait_cycles_async(&self, cycles: NonZeroUsize) -> CycleFuture {
+        self.notifier
+            .wait_async(self.notifier.cycle() + cycles.get() as u64)
+    }
+}
+
+/// Reorders the AUTD slaves so that the `i`-th device is the one identified by `ids[i]`.
+fn map_devices(
+    ctx: &Context,
+    device_slaves: &[usize],
+    ids: &[DeviceId],
+) -> Result<Vec<usize>, SOEMError> {
+    if ids.len() != device_slaves.len() {
+        return Err(SOEMError::DeviceIdMismatch(ids.len(), device_slaves.len()));
+    }
+    ids.iter()
+        .enumerate()
+        .try_fold(Vec::with_capacity(ids.len()), |mut mapped, (i, id)| {
+            let mut candidates = device_slaves
+                .iter()
+                .copied()
+                .filter(|&slave| id.matches(ctx.slave(slave)));
+            let slave = candidates.next().ok_or(SOEMError::DeviceNotFound(*id))?;
+            if candidates.next().is_some() || mapped.contains(&slave) {
+                return Err(SOEMError::DuplicateDeviceId(*id));
+            }
+            tracing::info!("Device[{}] is mapped to Slave[{}] by {}.", i, slave + 1, id);
+            mapped.push(slave);
+            Ok(mapped)
+        })
+}
+
+struct ProcessDataGroup {
+    group: u8,
+    /// Pairs of the device index and the slave index.
+    devices: Vec<(usize, usize)>,
+    foreign_slaves: Vec<usize>,
+    expected_wkc: i32,
 }
 
+impl ProcessDataGroup {
+    fn new(group: u8) -> Self {
+        Self {
+            group,
+            devices: Vec::new(),
+            foreign_slaves: Vec::new(),
+            expected_wkc: 0,
+        }
+    }
+
+    fn find_or_insert(groups: &mut Vec<Self>, group: u8) -> &mut Self {
+        match groups.iter().position(|g| g.group == group) {
+            Some(i) => &mut groups[i],
+            None => {
+                groups.push(Self::new(group));
+                groups.last_mut().unwrap()
+            }
+        }
+    }
+
+    fn iomap_size(&self) -> usize {
+        IOMap::size(self.devices.len()) + IOMap::FOREIGN_SLAVE_SIZE * self.foreign_slaves.len()
+    }
+}
+
+fn assign_groups(
+    ctx: &Context,
+    device_slaves: &[usize],
+    foreign_slaves: &[usize],
+    device_groups: Option<&[usize]>,
+) -> Result<Vec<ProcessDataGroup>, SOEMError> {
+    let Some(device_groups) = device_groups else {
+        let mut group = ProcessDataGroup::new(0);
+        group.devices = device_slaves.iter().copied().enumerate().collect();
+        group.foreign_slaves = foreign_slaves.to_vec();
+        return Ok(vec![group]);
+    };
+    if device_groups.len() != device_slaves.len() {
+        return Err(SOEMError::GroupMismatch(
+            device_groups.len(),
+            device_slaves.len(),
+        ));
+    }
+    // The group 0 of SOEM contains all slaves, so the groups are numbered from 1.
+    let mut groups: Vec<ProcessDataGroup> = Vec::new();
+    device_groups
+        .iter()
+        .zip(device_slaves.iter())
+        .enumerate()
+        .for_each(|(i, (&group, &slave))| {
+            let group = (group + 1) as u8;
+            ctx.set_group(slave, group);
+            ProcessDataGroup::find_or_insert(&mut groups, group)
+                .devices
+                .push((i, slave));
+        });
+    foreign_slaves.iter().for_each(|&slave| {
+        ctx.set_group(slave, 1);
+        ProcessDataGroup::find_or_insert(&mut groups, 1)
+            .foreign_slaves
+            .push(slave);
+    });
+    groups.sort_by_key(|g| g.group);
+    tracing::info!("Devices are partitioned into {} groups.", groups.len());
+    Ok(groups)
+}
+
+/// Returns the smoothed difference of the DC system time of each slave except the reference clock.
 fn wait_for_sync(
     ctx: &Context,
-    num_devices: usize,
     tolerance: Duration,
     timeout: Duration,
-) -> Result<(), SOEMError> {
+) -> Result<Vec<(usize, Duration)>, SOEMError> {
     tracing::info!("Waiting for synchronization.");
-    let max_diff = std::thread::scope(|s| {
+    let diffs = std::thread::scope(|s| {
         let (tx, rx) = sync_channel(1);
         let th = s.spawn(move || {
             let mut data = 0u64;
@@ -266,22 +785,29 @@ fn wait_for_sync(
 
         std::thread::sleep(Duration::from_millis(100));
 
-        let max_diff = if num_devices == 1 {
-            Duration::ZERO
+        // The first slave with DC is the reference clock, and slaves without DC are not synchronized.
+        let dc_slaves = ctx
+            .slaves()
+            .enumerate()
+            .filter(|(_, slave)| slave.hasdc != 0)
+            .skip(1)
+            .collect::<Vec<_>>();
+        let diffs = if dc_slaves.is_empty() {
+            Vec::new()
         } else {
-            let mut last_diff = (0..num_devices - 1)
+            let mut last_diff = dc_slaves
+                .iter()
                 .map(|_| tolerance.as_nanos() as u32)
                 .collect::<Vec<_>>();
-            let mut diff_averages = vec![Smoothing::new(0.2); num_devices - 1];
+            let mut diff_averages = vec![Smoothing::new(0.2); dc_slaves.len()];
             let start = std::time::Instant::now();
             loop {
-                let max_diff = ctx
-                    .slaves()
-                    .enumerate()
-                    .skip(1)
+                let diffs = dc_slaves
+                    .iter()
+                    .copied()
                     .zip(last_diff.iter_mut())
                     .zip(diff_averages.iter_mut())
-                    .fold(Duration::ZERO, |acc, (((i, slave), last_diff), ave)| {
+                    .map(|(((i, slave), last_diff), ave)| {
                         let mut diff: u32 = 0;
                         let res = ctx.fprd(
                             slave,
@@ -307,79 +833,202 @@ fn wait_for_sync(
                         };
                         let diff = Duration::from_nanos(ave.push(diff as _).abs() as _);
                         tracing::trace!("DCSYSDIFF[{}] = {:?}.", i + 1, diff);
-                        acc.max(diff)
-                    });
+                        (i, diff)
+                    })
+                    .collect::<Vec<_>>();
+                let max_diff = max_dc_diff(&diffs);
                 tracing::debug!("Maximum system time difference is {:?}.", max_diff);
                 if max_diff < tolerance || start.elapsed() > timeout {
-                    break max_diff;
+                    break diffs;
                 }
                 std::thread::sleep(Duration::from_millis(10));
             }
         };
         let _ = tx.send(());
         let _ = th.join();
-        max_diff
+        diffs
     });
 
+    let max_diff = max_dc_diff(&diffs);
     if max_diff < tolerance {
         tracing::info!(
             "All devices are synchronized. Maximum system time difference is {:?}.",
             max_diff
         );
-        Ok(())
+        Ok(diffs)
     } else {
         Err(SOEMError::SynchronizeFailed(max_diff, tolerance))
     }
 }
 
+fn max_dc_diff(diffs: &[(usize, Duration)]) -> Duration {
+    diffs
+        .iter()
+        .map(|&(_, diff)| diff)
+        .max()
+        .unwrap_or(Duration::ZERO)
+}
+
+/// If the EtherCAT thread lags behind the schedule by more than this number of cycles, the missed cycles are skipped.
+const MAX_LAG_CYCLES: u32 = 1000;
+
+fn set_affinity(affinity: Option<core_affinity::CoreId>) -> Result<(), SOEMError> {
+    if let Some(affinity) = affinity {
+        tracing::info!(
+            "Setting CPU affinity for the EtherCAT thread to {:?}",
+            affinity
+        );
+        if !core_affinity::set_for_current(affinity) {
+            tracing::error!("Failed to set CPU affinity for the EtherCAT thread.");
+            return Err(SOEMError::AffinitySetFailed(affinity));
+        }
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
-fn ecat_run<S: Sleeper>(
+fn ecat_run<F: Fn(u16, Status), S: Sleeper>(
     ctx: Arc<Context>,
     is_open: Arc<AtomicBool>,
     io_map: Arc<Mutex<IOMap>>,
-    expected_wkc: i32,
+    mut work: Vec<u8>,
+    notifier: Arc<CycleNotifier>,
+    dc_clock: Arc<Mutex<DcClockEstimator>>,
+    num_devices: usize,
+    groups: Vec<ProcessDataGroup>,
     do_wkc_check: Arc<AtomicI32>,
+    pending: Arc<AtomicUsize>,
     buffer_queue_sender: Sender<Vec<TxMessage>>,
     receiver: Receiver<Vec<TxMessage>>,
-    sleeper: S,
+    urgent_receiver: Receiver<(Vec<TxMessage>, bool)>,
+    sleeper: &CycleSleeper<S>,
     cycle: Duration,
+    deadline_miss_policy: DeadlineMissPolicy,
+    err_handler: Arc<F>,
 ) -> Result<(), SOEMError> {
     tracing::info!("Starting EtherCAT thread with cycle time {:?}.", cycle);
 
-    let mut cnt_miss_deadline = 0;
-    let mut toff = time::Duration::ZERO;
+    let _notifier = CloseOnDrop(notifier.clone());
+
+    let mut deadline_misses = DeadlineMissCounter::default();
+    let mut toff: i64 = 0;
     let mut timeerror = 0;
     let mut integral = 0;
-    ctx.send_processdata();
+    let mut stale = vec![false; num_devices];
+    let mut wkcs = vec![0; groups.len()];
+    // With a single group, the frame is sent at the end of each cycle and received at the beginning of the next one, so that the slaves have a whole cycle to process it.
+    // With multiple groups, the frames of each group are exchanged in turn within a cycle, because SOEM accumulates the working counters of all pending frames on receiving.
+    let pipelined = groups.len() == 1;
+    // The DC time is latched when the frame passes the reference clock, so it is paired with the host time at which the frame was sent rather than received.
+    let mut sent_at = Instant::now();
+    if pipelined {
+        ctx.send_processdata_group(groups[0].group);
+    }
+    // The cycles are scheduled on the monotonic clock, and UTC is only used to align the first cycle.
     let mut ts = {
-        let tp = time::OffsetDateTime::now_utc();
-        let tp_unix_ns = tp.unix_timestamp_nanos();
+        let tp_unix_ns = time::OffsetDateTime::now_utc().unix_timestamp_nanos();
+        let now = Instant::now();
         let cycle_ns = cycle.as_nanos() as i128;
         let ts_unix_ns = (tp_unix_ns / cycle_ns + 1) * cycle_ns;
-        time::OffsetDateTime::from_unix_timestamp_nanos(ts_unix_ns).unwrap()
+        now + Duration::from_nanos((ts_unix_ns - tp_unix_ns) as _)
     };
     while is_open.load(Ordering::Acquire) {
         ts += cycle;
-        ts += toff;
+        ts = if toff >= 0 {
+            ts + Duration::from_nanos(toff as _)
+        } else {
+            ts.checked_sub(Duration::from_nanos(toff.unsigned_abs()))
+                .unwrap_or(ts)
+        };
 
-        let duration = ts - time::OffsetDateTime::now_utc();
-        if duration > time::Duration::ZERO {
-            sleeper.sleep(std::time::Duration::from_nanos(
-                duration.whole_nanoseconds() as _,
-            ));
-            cnt_miss_deadline = 0;
+        let now = Instant::now();
+        if ts > now {
+            sleeper.sleep_until(ts);
+            deadline_misses.hit();
         } else {
-            cnt_miss_deadline += 1;
-            if cnt_miss_deadline == 1000 {
+            let lag = now - ts;
+            if lag > cycle * MAX_LAG_CYCLES {
+                // The thread has been stalled (e.g., the system was suspended), so skip the missed cycles instead of catching up.
+                tracing::warn!("EtherCAT thread was stalled for {:?}.", lag);
+                ts = realign(ts, now, cycle);
+            }
+            if let Some((consecutive, total)) = deadline_misses.miss(&deadline_miss_policy) {
                 tracing::warn!(
                     "Slow network was detected. Increase send_cycle and sync0_cycle and restart the program, or reboot the network adapter and device."
                 );
-                cnt_miss_deadline = 0;
+                (err_handler)(0, Status::DeadlineMissed { consecutive, total });
+                match deadline_miss_policy.action {
+                    DeadlineMissAction::CatchUp => {}
+                    DeadlineMissAction::Skip => {
+                        ts = realign(ts, now, cycle);
+                    }
+                    DeadlineMissAction::Close => {
+                        tracing::error!("Closing the link because of deadline misses.");
+                        is_open.store(false, Ordering::Release);
+                        break;
+                    }
+                }
             }
         }
 
-        if ctx.receive_processdata(EC_TIMEOUTRET as i32) != expected_wkc {
-            do_wkc_check.fetch_add(1, Ordering::Relaxed);
+        let mut tx = next_frame(&urgent_receiver, &receiver, &pending, &buffer_queue_sender);
+        let sent = tx.is_some();
+        let store_outputs = |io_map: &mut IOMap, work: &mut [u8], tx: Option<Vec<TxMessage>>| {
+            if let Some(tx) = tx {
+                io_map.copy_from(&tx);
+                let _ = buffer_queue_sender.send(tx);
+            }
+            io_map.store_outputs(work);
+        };
+
+        // The IOMap is not locked while the frames are on the wire.
+        if pipelined {
+            wkcs[0] = ctx.receive_processdata_group(groups[0].group, EC_TIMEOUTRET as i32);
+        } else {
+            match io_map.lock() {
+                Ok(mut io_map) => store_outputs(&mut io_map, &mut work, tx.take()),
+                Err(_) => {
+                    is_open.store(false, Ordering::Release);
+                    break;
+                }
+            }
+            for (group, wkc) in groups.iter().zip(wkcs.iter_mut()) {
+                sent_at = Instant::now();
+                ctx.send_processdata_group(group.group);
+                *wkc = ctx.receive_processdata_group(group.group, EC_TIMEOUTRET as i32);
+            }
+        }
+
+        // The inputs are loaded and the metadata is updated at once so that they are consistent.
+        match io_map.lock() {
+            Ok(mut io_map) => {
+                io_map.load_inputs(&work);
+                if pipelined {
+                    store_outputs(&mut io_map, &mut work, tx.take());
+                }
+                let (wkc, wkc_matched) = groups.iter().zip(wkcs.iter()).fold(
+                    (0, true),
+                    |(wkc, wkc_matched), (group, &group_wkc)| {
+                        let group_matched = group_wkc == group.expected_wkc;
+                        mark_stale(&ctx, &group.devices, group_matched, &mut stale);
+                        (wkc + group_wkc.max(0), wkc_matched && group_matched)
+                    },
+                );
+                if !wkc_matched {
+                    do_wkc_check.fetch_add(1, Ordering::Relaxed);
+                }
+                if sent {
+                    pending.fetch_sub(1, Ordering::AcqRel);
+                }
+                notifier.notify(ctx.dctime(), wkc, wkc_matched, &stale);
+                if wkc_matched && let Ok(mut dc_clock) = dc_clock.lock() {
+                    dc_clock.push(sent_at, ctx.dctime());
+                }
+            }
+            Err(_) => {
+                is_open.store(false, Ordering::Release);
+                break;
+            }
         }
 
         toff = ec_sync(
@@ -389,27 +1038,61 @@ fn ecat_run<S: Sleeper>(
             &mut integral,
         );
 
-        if let Ok(tx) = receiver.try_recv() {
-            match io_map.lock() {
-                Ok(mut io_map) => io_map.copy_from(&tx),
-                Err(_) => {
-                    is_open.store(false, Ordering::Release);
-                    break;
-                }
-            }
-            let _ = buffer_queue_sender.send(tx);
+        if pipelined {
+            sent_at = Instant::now();
+            ctx.send_processdata_group(groups[0].group);
         }
-        ctx.send_processdata();
     }
     Ok(())
 }
 
-fn ec_sync(
-    reftime: i64,
-    cycletime: i64,
-    timeerror: &mut i64,
-    integral: &mut i64,
-) -> time::Duration {
+/// Takes the frame to be sent in this cycle.
+///
+/// Frames in the urgent queue always take precedence over the normal queue. If the urgent frame requests a flush, the frames in the normal queue are discarded and their buffers are returned.
+fn next_frame(
+    urgent_receiver: &Receiver<(Vec<TxMessage>, bool)>,
+    receiver: &Receiver<Vec<TxMessage>>,
+    pending: &AtomicUsize,
+    buffer_queue_sender: &Sender<Vec<TxMessage>>,
+) -> Option<Vec<TxMessage>> {
+    match urgent_receiver.try_recv() {
+        Ok((tx, flush)) => {
+            if flush {
+                receiver.try_iter().for_each(|tx| {
+                    pending.fetch_sub(1, Ordering::AcqRel);
+                    let _ = buffer_queue_sender.send(tx);
+                });
+            }
+            Some(tx)
+        }
+        Err(_) => receiver.try_recv().ok(),
+    }
+}
+
+/// Skips the cycles missed by `now` keeping the phase of the schedule.
+fn realign(ts: Instant, now: Instant, cycle: Duration) -> Instant {
+    let skipped = (now - ts).as_nanos() / cycle.as_nanos();
+    tracing::warn!("Skipping {} cycles.", skipped);
+    ts + cycle * skipped as u32
+}
+
+fn mark_stale(ctx: &Context, devices: &[(usize, usize)], wkc_matched: bool, stale: &mut [bool]) {
+    if wkc_matched {
+        devices.iter().for_each(|&(i, _)| stale[i] = false);
+        return;
+    }
+    // The working counter does not tell which slave in the group failed to process the datagram.
+    // Slaves known to be unhealthy are marked as stale, and if there is no such slave, all inputs of the group are regarded as stale.
+    devices.iter().for_each(|&(i, slave)| {
+        let slave = ctx.slave(slave);
+        stale[i] = slave.islost != 0 || State::from(slave.state) != State::OPERATIONAL
+    });
+    if !devices.iter().any(|&(i, _)| stale[i]) {
+        devices.iter().for_each(|&(i, _)| stale[i] = true);
+    }
+}
+
+fn ec_sync(reftime: i64, cycletime: i64, timeerror: &mut i64, integral: &mut i64) -> i64 {
     const KP: f32 = 0.01;
     const KI: f32 = 0.00002;
     let mut delta = (reftime - 500000) % cycletime;
@@ -418,11 +1101,200 @@ fn ec_sync(
     }
     *timeerror = -delta;
     *integral += *timeerror;
-    (((KP * *timeerror as f32) + (KI * *integral as f32)) as i64).nanoseconds()
+    ((KP * *timeerror as f32) + (KI * *integral as f32)) as i64
 }
 
 impl Drop for SOEMHandler {
     fn drop(&mut self) {
-        self.close();
+        let _ = self.close(None);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inner::{ec_state_EC_STATE_OPERATIONAL, ec_state_EC_STATE_SAFE_OP};
+
+    #[test]
+    fn test_assign_groups() {
+        let ctx = Context::new();
+        let groups = assign_groups(&ctx, &[0, 1, 2], &[3], Some(&[1, 0, 1])).unwrap();
+
+        assert_eq!(2, groups.len());
+        assert_eq!(1, groups[0].group);
+        assert_eq!(vec![(1, 1)], groups[0].devices);
+        assert_eq!(vec![3], groups[0].foreign_slaves);
+        assert_eq!(2, groups[1].group);
+        assert_eq!(vec![(0, 0), (2, 2)], groups[1].devices);
+        assert!(groups[1].foreign_slaves.is_empty());
+        assert_eq!(
+            vec![2, 1, 2, 1],
+            (0..4).map(|i| ctx.slave(i).group).collect::<Vec<_>>()
+        );
+
+        let max_group = SOEMOptionFull::MAX_GROUPS - 1;
+        let groups = assign_groups(&ctx, &[0, 1], &[], Some(&[0, max_group])).unwrap();
+        assert_eq!(
+            vec![1, SOEMOptionFull::MAX_GROUPS as u8],
+            groups.iter().map(|g| g.group).collect::<Vec<_>>()
+        );
+
+        assert!(matches!(
+            assign_groups(&ctx, &[0, 1], &[], Some(&[0])),
+            Err(SOEMError::GroupMismatch(1, 2))
+        ));
+    }
+
+    #[test]
+    fn test_map_devices() {
+        let ctx = Context::new();
+        [(0x10, 100), (0x20, 200), (0x30, 200)]
+            .into_iter()
+            .enumerate()
+            .for_each(|(i, (alias, serial))| {
+                let slave = &mut ctx.ctx_mut().slavelist[i + 1];
+                slave.aliasadr = alias;
+                slave.eep_ser = serial;
+            });
+
+        assert_eq!(
+            vec![2, 0, 1],
+            map_devices(
+                &ctx,
+                &[0, 1, 2],
+                &[
+                    DeviceId::Alias(0x30),
+                    DeviceId::Alias(0x10),
+                    DeviceId::Alias(0x20)
+                ]
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            vec![1, 0],
+            map_devices(
+                &ctx,
+                &[0, 1],
+                &[DeviceId::Serial(200), DeviceId::Alias(0x10)]
+            )
+            .unwrap()
+        );
+
+        assert!(matches!(
+            map_devices(
+                &ctx,
+                &[0, 1],
+                &[DeviceId::Alias(0x10), DeviceId::Alias(0x40)]
+            ),
+            Err(SOEMError::DeviceNotFound(DeviceId::Alias(0x40)))
+        ));
+        assert!(matches!(
+            map_devices(
+                &ctx,
+                &[0, 1, 2],
+                &[
+                    DeviceId::Alias(0x10),
+                    DeviceId::Serial(200),
+                    DeviceId::Alias(0x20)
+                ]
+            ),
+            Err(SOEMError::DuplicateDeviceId(DeviceId::Serial(200)))
+        ));
+        assert!(matches!(
+            map_devices(
+                &ctx,
+                &[0, 1],
+                &[DeviceId::Alias(0x10), DeviceId::Alias(0x10)]
+            ),
+            Err(SOEMError::DuplicateDeviceId(DeviceId::Alias(0x10)))
+        ));
+        let err = map_devices(&ctx, &[0, 1, 2], &[DeviceId::Alias(0x10)]).unwrap_err();
+        assert!(matches!(err, SOEMError::DeviceIdMismatch(1, 3)));
+        assert!(!err.is_transient());
+    }
+
+    #[test]
+    fn test_mark_stale() {
+        let ctx = Context::new();
+        (1..=3).for_each(|i| ctx.ctx_mut().slavelist[i].state = ec_state_EC_STATE_OPERATIONAL as _);
+        let devices = [(0, 0), (2, 2)];
+        let mut stale = vec![false, true, false];
+
+        // No slave in the group is known to be unhealthy, so all inputs of the group are stale.
+        mark_stale(&ctx, &devices, false, &mut stale);
+        assert_eq!(vec![true, true, true], stale);
+
+        mark_stale(&ctx, &devices, true, &mut stale);
+        assert_eq!(vec![false, true, false], stale);
+
+        // Only the inputs of the lost slave are stale.
+        ctx.ctx_mut().slavelist[3].islost = 1;
+        mark_stale(&ctx, &devices, false, &mut stale);
+        assert_eq!(vec![false, true, true], stale);
+
+        // Only the inputs of the slave not in OPERATIONAL are stale.
+        ctx.ctx_mut().slavelist[3].islost = 0;
+        ctx.ctx_mut().slavelist[1].state = ec_state_EC_STATE_SAFE_OP as _;
+        mark_stale(&ctx, &devices, false, &mut stale);
+        assert_eq!(vec![true, true, false], stale);
+    }
+
+    #[test]
+    fn test_realign() {
+        let cycle = Duration::from_millis(1);
+        let ts = Instant::now();
+
+        // The schedule keeps the phase, and the next cycle is the first one after `now`.
+        let now = ts + cycle * 7 / 2;
+        let realigned = realign(ts, now, cycle);
+        assert_eq!(ts + cycle * 3, realigned);
+        assert!(realigned <= now && now < realigned + cycle);
+
+        let realigned = realign(ts, ts + cycle * 5, cycle);
+        assert_eq!(ts + cycle * 5, realigned);
+
+        assert_eq!(ts, realign(ts, ts + cycle / 2, cycle));
+    }
+
+    #[test]
+    fn test_next_frame() {
+        let (sender, receiver) = sync_channel(4);
+        let (urgent_sender, urgent_receiver) = sync_channel(4);
+        let (buffer_queue_sender, buffer_queue_receiver) = sync_channel(4);
+        let pending = AtomicUsize::new(0);
+        // The frames are identified by their lengths.
+        let frame = |len: usize| {
+            pending.fetch_add(1, Ordering::AcqRel);
+            vec![TxMessage::new(); len]
+        };
+        let next = || {
+            next_frame(&urgent_receiver, &receiver, &pending, &buffer_queue_sender)
+                .map(|tx| tx.len())
+        };
+
+        assert_eq!(None, next());
+
+        // The urgent queue is drained first.
+        sender.send(frame(1)).unwrap();
+        sender.send(frame(2)).unwrap();
+        urgent_sender.send((frame(3), false)).unwrap();
+        assert_eq!(Some(3), next());
+        assert_eq!(Some(1), next());
+        assert_eq!(3, pending.load(Ordering::Acquire));
+        assert!(buffer_queue_receiver.try_recv().is_err());
+
+        // A flush discards the queued frames.
+        sender.send(frame(4)).unwrap();
+        urgent_sender.send((frame(5), true)).unwrap();
+        assert_eq!(Some(5), next());
+        assert_eq!(3, pending.load(Ordering::Acquire));
+        assert_eq!(
+            vec![2, 4],
+            buffer_queue_receiver
+                .try_iter()
+                .map(|tx| tx.len())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(None, next());
     }
 }
@@ -0,0 +1,214 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    ffi::CString,
+    sync::mpsc::channel,
+    time::{Duration, Instant},
+};
+
+use crate::error::SOEMError;
+
+use super::{Context, EthernetAdapters, utils::is_autd3};
+
+/// A network interface on which AUTD devices are found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceCandidate {
+    /// The name of the interface.
+    pub name: String,
+    /// The description of the interface.
+    pub desc: String,
+    /// The number of AUTD devices on the bus.
+    pub num_devices: usize,
+    /// The number of slaves on the bus including the slaves other than AUTD devices.
+    pub num_slaves: usize,
+}
+
+impl InterfaceCandidate {
+    /// Probes the network interfaces in parallel and returns the ones on which AUTD devices are found in the order of [`EthernetAdapters`].
+    ///
+    /// If `filter` is specified, only the interfaces whose name or description contains it (case-insensitive) are probed. The interfaces which do not respond within `timeout` are skipped with a warning. Note that probing resets the slaves to the init state, so this must not be called while a link is opened.
+    pub fn scan(filter: Option<&str>, timeout: Duration) -> Vec<InterfaceCandidate> {
+        probe_all(filter, timeout).0
+    }
+}
+
+impl std::fmt::Display for InterfaceCandidate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}, {} ({} AUTD devices, {} slaves)",
+            self.name, self.desc, self.num_devices, self.num_slaves
+        )
+    }
+}
+
+fn matches_filter(name: &str, desc: &str, filter: Option<&str>) -> bool {
+    filter.is_none_or(|filter| {
+        let filter = filter.to_lowercase();
+        name.to_lowercase().contains(&filter) || desc.to_lowercase().contains(&filter)
+    })
+}
+
+fn probe(name: &str, desc: &str) -> Option<InterfaceCandidate> {
+    let ifname = CString::new(name).ok()?;
+    tracing::debug!("Searching AUTD device on {}.", name);
+    let ctx = Context::new();
+    if ctx.init(ifname).is_err() {
+        tracing::trace!("Failed to initialize SOEM on {}.", name);
+        return None;
+    }
+    let Some(wc) = ctx.config_init() else {
+        tracing::trace!("No slave found on {}.", name);
+        return None;
+    };
+    tracing::trace!("Found {} slaves on {}.", wc, name);
+    let num_devices = ctx.slaves().filter(|slave| is_autd3(slave)).count();
+    (num_devices > 0).then(|| InterfaceCandidate {
+        name: name.to_owned(),
+        desc: desc.to_owned(),
+        num_devices,
+        num_slaves: wc,
+    })
+}
+
+/// Probes the network interfaces in parallel and returns the candidates in the order of [`EthernetAdapters`] and the names of the interfaces which did not respond within `timeout`.
+///
+/// The probes which did not finish in time are left running in the background. They close their sockets on their own when the probe finishes, and their results are discarded.
+fn probe_all(filter: Option<&str>, timeout: Duration) -> (Vec<InterfaceCandidate>, Vec<String>) {
+    let adapters = EthernetAdapters::new();
+    tracing::debug!("Found {} network adapters.", adapters.len());
+    let (tx, rx) = channel();
+    let names = adapters
+        .into_iter()
+        .filter(|adapter| matches_filter(adapter.name(), adapter.desc(), filter))
+        .enumerate()
+        .map(|(i, adapter)| {
+            let name = adapter.name().to_owned();
+            let tx = tx.clone();
+            std::thread::spawn(move || {
+                let _ = tx.send((i, probe(adapter.name(), adapter.desc())));
+            });
+            name
+        })
+        .collect::<Vec<_>>();
+
+    let deadline = Instant::now() + timeout;
+    let mut responded = vec![false; names.len()];
+    let mut candidates = Vec::new();
+    for _ in 0..names.len() {
+        match rx.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok((i, candidate)) => {
+                responded[i] = true;
+                candidates.extend(candidate.map(|c| (i, c)));
+            }
+            Err(_) => break,
+        }
+    }
+    let timed_out = names
+        .into_iter()
+        .zip(responded)
+        .filter_map(|(name, responded)| (!responded).then_some(name))
+        .collect::<Vec<_>>();
+    if !timed_out.is_empty() {
+        tracing::warn!(
+            "Probing network adapters ({}) timed out after {:?}.",
+            timed_out.join(", "),
+            timeout
+        );
+    }
+    candidates.sort_by_key(|&(i, _)| i);
+    (candidates.into_iter().map(|(_, c)| c).collect(), timed_out)
+}
+
+fn select(
+    candidates: Vec<InterfaceCandidate>,
+    allow_foreign_slaves: bool,
+    expected_devices: Option<usize>,
+) -> Result<InterfaceCandidate, SOEMError> {
+    let mut candidates = candidates
+        .into_iter()
+        .filter(|c| allow_foreign_slaves || c.num_devices == c.num_slaves)
+        .filter(|c| expected_devices.is_none_or(|expected| c.num_devices == expected))
+        .collect::<Vec<_>>();
+    match candidates.len() {
+        0 => Err(SOEMError::NoDeviceFound),
+        1 => Ok(candidates.remove(0)),
+        _ => Err(SOEMError::AmbiguousInterface(candidates)),
+    }
+}
+
+pub fn lookup_autd(
+    allow_foreign_slaves: bool,
+    expected_devices: Option<usize>,
+    filter: Option<&str>,
+    timeout: Duration,
+) -> Result<CString, SOEMError> {
+    let (candidates, timed_out) = probe_all(filter, timeout);
+    // The interfaces which did not respond may have AUTD devices, so the selection would not be reliable.
+    if !timed_out.is_empty() {
+        return Err(SOEMError::ProbeTimeout(timed_out));
+    }
+    let candidate = select(candidates, allow_foreign_slaves, expected_devices)?;
+    CString::new(candidate.name.as_str())
+        .map_err(|_| SOEMError::InvalidInterfaceName(candidate.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(name: &str, num_devices: usize, num_slaves: usize) -> InterfaceCandidate {
+        InterfaceCandidate {
+            name: name.to_owned(),
+            desc: String::new(),
+            num_devices,
+            num_slaves,
+        }
+    }
+
+    #[test]
+    fn test_matches_filter() {
+        assert!(matches_filter("eth0", "Intel I210", None));
+        assert!(matches_filter("eth0", "Intel I210", Some("ETH")));
+        assert!(matches_filter("eth0", "Intel I210", Some("i210")));
+        assert!(!matches_filter("eth0", "Intel I210", Some("enp")));
+    }
+
+    #[test]
+    fn test_select() {
+        assert_eq!(
+            candidate("eth1", 2, 2),
+            select(
+                vec![candidate("eth0", 1, 1), candidate("eth1", 2, 2)],
+                false,
+                Some(2)
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            candidate("eth1", 2, 2),
+            select(
+                vec![candidate("eth0", 2, 3), candidate("eth1", 2, 2)],
+                false,
+                None
+            )
+            .unwrap()
+        );
+        assert!(matches!(
+            select(vec![candidate("eth0", 2, 3), candidate("eth1", 2, 2)], true, None),
+            Err(SOEMError::AmbiguousInterface(c)) if c.len() == 2
+        ));
+        assert!(matches!(
+            select(vec![candidate("eth0", 1, 1)], false, Some(2)),
+            Err(SOEMError::NoDeviceFound)
+        ));
+    }
+}
@@ -8,14 +8,33 @@
 //
 // You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
 
+use std::ops::Range;
+
 use autd3_core::{
     ethercat::{EC_INPUT_FRAME_SIZE, EC_OUTPUT_FRAME_SIZE},
     link::{RxMessage, TxMessage},
 };
 
+/// The process data shared with the user.
+///
+/// SOEM exchanges the process data through a working buffer of the same layout owned by the EtherCAT thread, so that the frames can be sent and received without locking this map.
+/// The outputs are stored to and the inputs are loaded from the working buffer once per cycle.
 pub struct IOMap {
     buf: Vec<u8>,
-    num_devices: usize,
+    outputs: Vec<usize>,
+    inputs: Vec<usize>,
+    /// The ranges of the outputs and inputs of each group.
+    groups: Vec<(Range<usize>, Range<usize>)>,
+    /// The ranges of the outputs and inputs of each slave other than AUTD devices.
+    foreign_slaves: Vec<(Range<usize>, Range<usize>)>,
+}
+
+fn range(base: *const u8, ptr: *const u8, len: u32) -> Range<usize> {
+    if len == 0 {
+        return 0..0;
+    }
+    let start = unsafe { ptr.offset_from(base) } as usize;
+    start..start + len as usize
 }
 
 impl std::ops::Deref for IOMap {
@@ -27,37 +46,119 @@ impl std::ops::Deref for IOMap {
 }
 
 impl IOMap {
+    pub const FOREIGN_SLAVE_SIZE: usize = 1024;
+
+    pub const fn size(num_devices: usize) -> usize {
+        (1 + EC_OUTPUT_FRAME_SIZE + EC_INPUT_FRAME_SIZE) * num_devices
+    }
+
+    #[cfg(test)]
     pub fn new(num_devices: usize) -> Self {
-        let size = (1 + EC_OUTPUT_FRAME_SIZE + EC_INPUT_FRAME_SIZE) * num_devices;
+        Self::with_size(Self::size(num_devices), num_devices)
+    }
+
+    pub fn with_size(size: usize, num_devices: usize) -> Self {
         Self {
             buf: vec![0x00; size],
-            num_devices,
+            outputs: (0..num_devices).map(|i| i * EC_OUTPUT_FRAME_SIZE).collect(),
+            inputs: (0..num_devices)
+                .map(|i| num_devices * EC_OUTPUT_FRAME_SIZE + i * EC_INPUT_FRAME_SIZE)
+                .collect(),
+            groups: Vec::new(),
+            foreign_slaves: Vec::new(),
         }
     }
 
-    pub fn input(&self) -> &[RxMessage] {
-        unsafe {
-            std::slice::from_raw_parts(
-                self.buf[self.num_devices * EC_OUTPUT_FRAME_SIZE..].as_ptr() as *const RxMessage,
-                self.num_devices,
-            )
-        }
+    /// Updates the offsets of the process data of each device from the pointers assigned by SOEM in the working buffer starting at `base`.
+    pub fn remap(
+        &mut self,
+        base: *const u8,
+        devices: impl IntoIterator<Item = (*const u8, *const u8)>,
+    ) {
+        self.outputs.clear();
+        self.inputs.clear();
+        devices.into_iter().for_each(|(outputs, inputs)| {
+            self.outputs.push(unsafe { outputs.offset_from(base) } as _);
+            self.inputs.push(unsafe { inputs.offset_from(base) } as _);
+        });
+    }
+
+    /// Sets the ranges of the process data of each group from the pointers and sizes of the outputs and inputs assigned by SOEM in the working buffer starting at `base`.
+    pub fn map_groups(
+        &mut self,
+        base: *const u8,
+        groups: impl IntoIterator<Item = ((*const u8, u32), (*const u8, u32))>,
+    ) {
+        self.groups = groups
+            .into_iter()
+            .map(|((outputs, obytes), (inputs, ibytes))| {
+                (range(base, outputs, obytes), range(base, inputs, ibytes))
+            })
+            .collect();
+    }
+
+    /// Sets the ranges of the process data of each slave other than AUTD devices in the same way as [`IOMap::map_groups`].
+    pub fn map_foreign_slaves(
+        &mut self,
+        base: *const u8,
+        slaves: impl IntoIterator<Item = ((*const u8, u32), (*const u8, u32))>,
+    ) {
+        self.foreign_slaves = slaves
+            .into_iter()
+            .map(|((outputs, obytes), (inputs, ibytes))| {
+                (range(base, outputs, obytes), range(base, inputs, ibytes))
+            })
+            .collect();
+    }
+
+    pub fn foreign_inputs(&self, idx: usize) -> &[u8] {
+        &self.buf[self.foreign_slaves[idx].1.clone()]
+    }
+
+    pub fn foreign_outputs_mut(&mut self, idx: usize) -> &mut [u8] {
+        let range = self.foreign_slaves[idx].0.clone();
+        &mut self.buf[range]
+    }
+
+    /// Copies the outputs of all groups to the working buffer.
+    pub fn store_outputs(&self, work: &mut [u8]) {
+        self.groups.iter().for_each(|(outputs, _)| {
+            work[outputs.clone()].copy_from_slice(&self.buf[outputs.clone()]);
+        });
+    }
+
+    /// Copies the inputs of all groups from the working buffer.
+    pub fn load_inputs(&mut self, work: &[u8]) {
+        self.groups.iter().for_each(|(_, inputs)| {
+            self.buf[inputs.clone()].copy_from_slice(&work[inputs.clone()]);
+        });
+    }
+
+    pub fn copy_to(&self, rx: &mut [RxMessage]) {
+        rx.iter_mut()
+            .zip(self.inputs.iter())
+            .for_each(|(rx, &offset)| unsafe {
+                *rx = std::ptr::read_unaligned(self.buf[offset..].as_ptr() as *const RxMessage);
+            });
     }
 
     pub fn copy_from(&mut self, tx: &[TxMessage]) {
-        unsafe {
-            std::ptr::copy_nonoverlapping(
-                tx.as_ptr() as *const u8,
-                self.buf[0..].as_mut_ptr(),
-                std::mem::size_of_val(tx),
-            );
-        }
+        tx.iter()
+            .zip(self.outputs.iter())
+            .for_each(|(tx, &offset)| unsafe {
+                std::ptr::copy_nonoverlapping(
+                    tx as *const TxMessage as *const u8,
+                    self.buf[offset..].as_mut_ptr(),
+                    std::mem::size_of::<TxMessage>(),
+                );
+            });
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use autd3_core::link::Ack;
 
     #[test]
     fn test_iomap() {
@@ -78,4 +179,90 @@ mod tests {
         assert_eq!(iomap[3 + 1], 0x04);
         assert_eq!(iomap[3 + payload_size], 0x05);
     }
+
+    #[test]
+    fn test_iomap_remap() {
+        // The devices are mapped in reverse order as if they belong to different groups.
+        let mut iomap = IOMap::new(2);
+        let work = vec![0x00; IOMap::size(2)];
+        let base = work.as_ptr();
+        iomap.remap(
+            base,
+            [IOMap::size(1), 0].map(|offset| unsafe {
+                (base.add(offset), base.add(offset + EC_OUTPUT_FRAME_SIZE))
+            }),
+        );
+
+        let mut tx = vec![TxMessage::new(); 2];
+        tx[0].header.msg_id = autd3_core::link::MsgId::new(0x01);
+        tx[1].header.msg_id = autd3_core::link::MsgId::new(0x02);
+        iomap.copy_from(&tx);
+        assert_eq!(iomap[0], 0x02);
+        assert_eq!(iomap[IOMap::size(1)], 0x01);
+
+        iomap.buf[EC_OUTPUT_FRAME_SIZE] = 0x03;
+        iomap.buf[IOMap::size(1) + EC_OUTPUT_FRAME_SIZE] = 0x04;
+        let mut rx = vec![RxMessage::new(0, Ack::new(0, 0)); 2];
+        iomap.copy_to(&mut rx);
+        assert_eq!(0x04, rx[0].data());
+        assert_eq!(0x03, rx[1].data());
+    }
+
+    #[test]
+    fn test_iomap_exchange() {
+        // Each device belongs to its own group.
+        let mut iomap = IOMap::new(2);
+        let mut work = vec![0x00; IOMap::size(2)];
+        let base = work.as_ptr();
+        let groups = [0, IOMap::size(1)].map(|offset| unsafe {
+            (
+                (base.add(offset), EC_OUTPUT_FRAME_SIZE as u32),
+                (
+                    base.add(offset + EC_OUTPUT_FRAME_SIZE),
+                    EC_INPUT_FRAME_SIZE as u32,
+                ),
+            )
+        });
+        iomap.remap(
+            base,
+            groups.map(|((outputs, _), (inputs, _))| (outputs, inputs)),
+        );
+        iomap.map_groups(base, groups);
+
+        let mut tx = vec![TxMessage::new(); 2];
+        tx[0].header.slot_2_offset = 0x0201;
+        tx[1].header.slot_2_offset = 0x0403;
+        iomap.copy_from(&tx);
+        iomap.store_outputs(&mut work);
+        assert_eq!(0x01, work[2]);
+        assert_eq!(0x03, work[IOMap::size(1) + 2]);
+        assert_eq!(0x00, work[EC_OUTPUT_FRAME_SIZE]);
+
+        work[EC_OUTPUT_FRAME_SIZE] = 0x05;
+        work[IOMap::size(1) + EC_OUTPUT_FRAME_SIZE] = 0x06;
+        iomap.load_inputs(&work);
+        let mut rx = vec![RxMessage::new(0, Ack::new(0, 0)); 2];
+        iomap.copy_to(&mut rx);
+        assert_eq!(0x05, rx[0].data());
+        assert_eq!(0x06, rx[1].data());
+    }
+
+    #[test]
+    fn test_iomap_foreign_slaves() {
+        // A group of 4 bytes of outputs and 2 bytes of inputs, which contains a slave without inputs.
+        let mut iomap = IOMap::with_size(6, 0);
+        let mut work = vec![0x00; 6];
+        let base = work.as_ptr();
+        iomap.map_groups(base, [unsafe { ((base, 4), (base.add(4), 2)) }]);
+        iomap.map_foreign_slaves(base, [unsafe { ((base.add(2), 2), (std::ptr::null(), 0)) }]);
+
+        iomap.foreign_outputs_mut(0).copy_from_slice(&[0x01, 0x02]);
+        iomap.store_outputs(&mut work);
+        assert_eq!(vec![0, 0, 1, 2, 0, 0], work);
+
+        work[4] = 0x03;
+        iomap.load_inputs(&work);
+        assert_eq!(0x03, iomap[4]);
+        assert!(iomap.foreign_inputs(0).is_empty());
+    }
 }
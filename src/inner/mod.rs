@@ -8,21 +8,45 @@
 //
 // You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
 
+mod al_status_code;
 mod context;
+mod cycle;
+mod dc_clock;
+mod device_info;
 mod ethernet_adapters;
+mod foreign_slave;
 mod handler;
+mod interface_candidate;
 mod iomap;
+mod open_report;
 mod option;
+mod preflight;
+#[cfg(target_os = "linux")]
+mod rt_readiness;
+mod slave_error;
 mod smoothing;
 mod soem_bindings;
 mod state;
 mod status;
 mod utils;
 
+pub use al_status_code::AlStatusCode;
 pub use context::*;
-pub use ethernet_adapters::EthernetAdapters;
+pub use cycle::RxMetadata;
+pub use dc_clock::DcClockMapping;
+pub use device_info::{DeviceInfo, MailboxProtocols};
+pub use ethernet_adapters::{Duplex, EthernetAdapter, EthernetAdapters, MacAddress, OperState};
+pub use foreign_slave::ForeignSlave;
 pub use handler::SOEMHandler;
-pub use option::{SOEMOption, SOEMOptionFull};
+pub use interface_candidate::InterfaceCandidate;
+pub use open_report::{OpenReport, OpenStage, SlaveReport};
+pub use option::{
+    DeadlineMissAction, DeadlineMissPolicy, DeviceId, RetryPolicy, SOEMOption, SOEMOptionFull,
+};
+pub use preflight::preflight_check;
+#[cfg(target_os = "linux")]
+pub use rt_readiness::{Coalescing, NicIrq, RtReadinessReport};
+pub use slave_error::{SlaveError, SlaveErrorKind};
 pub use soem_bindings::*;
 pub use state::State;
 pub use status::Status;
@@ -30,6 +54,7 @@ pub use status::Status;
 pub mod consts {
     pub const EC_TIMEOUTSTATE: u32 = super::soem_bindings::EC_TIMEOUTSTATE;
     pub const EC_TIMEOUTRET: u32 = super::soem_bindings::EC_TIMEOUTRET;
+    pub const EC_MAXGROUP: u32 = super::soem_bindings::EC_MAXGROUP;
 
     pub const ECT_REG_DCSYSTIME: u16 = super::soem_bindings::ECT_REG_DCSYSTIME as _;
     pub const ECT_REG_DCSYSDIFF: u16 = super::soem_bindings::ECT_REG_DCSYSDIFF as _;
@@ -0,0 +1,90 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::{Duration, Instant};
+
+use super::{AlStatusCode, DeviceInfo, State};
+
+/// A stage of opening the link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpenStage {
+    /// Initializing SOEM on the network interface.
+    Init,
+    /// Enumerating and configuring the slaves.
+    ConfigInit,
+    /// Configuring distributed clocks and Sync0.
+    ConfigDc,
+    /// Waiting for the DC system times of the slaves to be synchronized.
+    Synchronize,
+    /// Mapping the process data to the IOMap.
+    MapProcessData,
+    /// Waiting for the slaves to reach the safe operational state.
+    SafeOp,
+    /// Starting the EtherCAT thread and waiting for the slaves to reach the operational state.
+    Operational,
+}
+
+/// A report of a slave at the end of opening the link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlaveReport {
+    /// The identity of the slave.
+    pub info: DeviceInfo,
+    /// The state of the slave.
+    pub state: State,
+    /// The smoothed difference of the DC system time from the reference clock at the end of the synchronization. `None` for the reference clock and the slaves without DC.
+    pub dc_diff: Option<Duration>,
+    /// The AL status code of the slave.
+    pub al_status_code: AlStatusCode,
+}
+
+/// A report of opening the link.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenReport {
+    /// The name of the network interface.
+    pub ifname: String,
+    /// The reports of all slaves on the bus.
+    pub slaves: Vec<SlaveReport>,
+    /// The maximum difference of the DC system time at the end of the synchronization.
+    pub max_dc_diff: Duration,
+    /// The duration of each stage in order.
+    pub stages: Vec<(OpenStage, Duration)>,
+}
+
+impl OpenReport {
+    /// The total duration of opening the link.
+    pub fn total(&self) -> Duration {
+        self.stages.iter().map(|(_, d)| *d).sum()
+    }
+}
+
+pub struct StageTimer {
+    last: Instant,
+    stages: Vec<(OpenStage, Duration)>,
+}
+
+impl StageTimer {
+    pub fn new() -> Self {
+        Self {
+            last: Instant::now(),
+            stages: Vec::new(),
+        }
+    }
+
+    pub fn finish(&mut self, stage: OpenStage) {
+        let now = Instant::now();
+        self.stages.push((stage, now - self.last));
+        tracing::debug!("{:?} stage took {:?}.", stage, now - self.last);
+        self.last = now;
+    }
+
+    pub fn into_stages(self) -> Vec<(OpenStage, Duration)> {
+        self.stages
+    }
+}
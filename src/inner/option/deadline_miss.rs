@@ -0,0 +1,134 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::num::NonZeroUsize;
+
+/// The action taken when the threshold of [`DeadlineMissPolicy`] is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DeadlineMissAction {
+    /// Keeps the schedule and tries to catch up with it.
+    #[default]
+    CatchUp,
+    /// Skips the missed cycles to realign the schedule.
+    Skip,
+    /// Closes the link.
+    Close,
+}
+
+/// A policy for the deadline misses of the EtherCAT thread.
+///
+/// When a threshold is reached, [`Status::DeadlineMissed`] is reported to the status handler and [`DeadlineMissPolicy::action`] is taken.
+/// Note that the status handler is called from the EtherCAT thread in this case.
+///
+/// [`Status::DeadlineMissed`]: crate::Status::DeadlineMissed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadlineMissPolicy {
+    /// The number of consecutive deadline misses to trigger the action. If `None`, consecutive misses are not checked. The default is 1000.
+    pub consecutive_threshold: Option<NonZeroUsize>,
+    /// The action is triggered every time the total number of deadline misses reaches a multiple of this value. If `None`, the total is not checked. The default is `None`.
+    pub total_threshold: Option<NonZeroUsize>,
+    /// The action taken when a threshold is reached. The default is [`DeadlineMissAction::CatchUp`].
+    pub action: DeadlineMissAction,
+}
+
+impl Default for DeadlineMissPolicy {
+    fn default() -> Self {
+        Self {
+            consecutive_threshold: NonZeroUsize::new(1000),
+            total_threshold: None,
+            action: DeadlineMissAction::CatchUp,
+        }
+    }
+}
+
+impl DeadlineMissPolicy {
+    pub(crate) fn is_triggered(&self, consecutive: usize, total: usize) -> bool {
+        self.consecutive_threshold
+            .is_some_and(|threshold| consecutive == threshold.get())
+            || self
+                .total_threshold
+                .is_some_and(|threshold| total.is_multiple_of(threshold.get()))
+    }
+}
+
+/// Counts the deadline misses of the EtherCAT thread.
+#[derive(Debug, Default)]
+pub(crate) struct DeadlineMissCounter {
+    consecutive: usize,
+    total: usize,
+}
+
+impl DeadlineMissCounter {
+    /// Records a cycle which met the deadline.
+    pub(crate) fn hit(&mut self) {
+        self.consecutive = 0;
+    }
+
+    /// Records a deadline miss, and returns the consecutive and total number of misses if a threshold of `policy` is reached.
+    ///
+    /// The consecutive count is reset when the action is triggered, so the action is triggered again after another [`DeadlineMissPolicy::consecutive_threshold`] misses.
+    pub(crate) fn miss(&mut self, policy: &DeadlineMissPolicy) -> Option<(usize, usize)> {
+        self.consecutive += 1;
+        self.total += 1;
+        policy
+            .is_triggered(self.consecutive, self.total)
+            .then(|| (std::mem::take(&mut self.consecutive), self.total))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline_miss_policy_is_triggered() {
+        let policy = DeadlineMissPolicy {
+            consecutive_threshold: NonZeroUsize::new(3),
+            total_threshold: NonZeroUsize::new(5),
+            ..Default::default()
+        };
+        assert!(!policy.is_triggered(1, 1));
+        assert!(!policy.is_triggered(2, 2));
+        assert!(policy.is_triggered(3, 3));
+        assert!(!policy.is_triggered(4, 4));
+        assert!(policy.is_triggered(1, 5));
+        assert!(!policy.is_triggered(2, 6));
+        assert!(policy.is_triggered(2, 10));
+
+        let policy = DeadlineMissPolicy {
+            consecutive_threshold: None,
+            total_threshold: None,
+            ..Default::default()
+        };
+        assert!(!policy.is_triggered(1000, 1000));
+    }
+
+    #[test]
+    fn test_deadline_miss_counter() {
+        let policy = DeadlineMissPolicy {
+            consecutive_threshold: NonZeroUsize::new(3),
+            total_threshold: NonZeroUsize::new(8),
+            ..Default::default()
+        };
+        let mut counter = DeadlineMissCounter::default();
+        assert_eq!(None, counter.miss(&policy));
+        assert_eq!(None, counter.miss(&policy));
+        assert_eq!(Some((3, 3)), counter.miss(&policy));
+        // The consecutive count starts over after the trigger.
+        assert_eq!(None, counter.miss(&policy));
+        assert_eq!(None, counter.miss(&policy));
+        assert_eq!(Some((3, 6)), counter.miss(&policy));
+        assert_eq!(None, counter.miss(&policy));
+        // A cycle which met the deadline resets only the consecutive count.
+        counter.hit();
+        assert_eq!(Some((1, 8)), counter.miss(&policy));
+        assert_eq!(None, counter.miss(&policy));
+    }
+}
@@ -0,0 +1,38 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::inner::ec_slave;
+
+/// An identifier of an AUTD device on the bus, which does not depend on the cabling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DeviceId {
+    /// The configured station alias.
+    Alias(u16),
+    /// The serial number in the EEPROM.
+    Serial(u32),
+}
+
+impl DeviceId {
+    pub(crate) fn matches(&self, slave: &ec_slave) -> bool {
+        match *self {
+            DeviceId::Alias(alias) => slave.aliasadr == alias,
+            DeviceId::Serial(serial) => slave.eep_ser == serial,
+        }
+    }
+}
+
+impl std::fmt::Display for DeviceId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceId::Alias(alias) => write!(f, "alias {:#06X}", alias),
+            DeviceId::Serial(serial) => write!(f, "serial number {}", serial),
+        }
+    }
+}
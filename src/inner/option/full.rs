@@ -14,7 +14,13 @@ use autd3_core::ethercat::EC_CYCLE_TIME_BASE;
 
 use thread_priority::ThreadBuilder;
 
-use crate::{SOEMOption, error::SOEMError};
+use crate::{
+    SOEMOption,
+    error::SOEMError,
+    inner::{EthernetAdapters, MacAddress, consts::EC_MAXGROUP, iomap::IOMap},
+};
+
+use super::{DeadlineMissPolicy, DeviceId, RetryPolicy};
 
 /// A option for [`SOEM`].
 ///
@@ -24,7 +30,17 @@ pub struct SOEMOptionFull {
     /// The size of the send queue buffer. The default is 16.
     pub buf_size: NonZeroUsize,
     /// The network interface name. If `None`, the network interface will be automatically selected to which the AUTD3 device is connected. The default is `None`.
+    ///
+    /// The automatic selection fails if AUTD devices matching the geometry are found on more than one interface. See also [`InterfaceCandidate::scan`].
+    ///
+    /// [`InterfaceCandidate::scan`]: crate::InterfaceCandidate::scan
     pub ifname: Option<String>,
+    /// If `Some`, the network interface is selected by the MAC address instead of the name, which may change between machines. Ignored if [`SOEMOptionFull::ifname`](field@SOEMOptionFull::ifname) is specified. Only supported on Linux. The default is `None`.
+    pub mac_address: Option<MacAddress>,
+    /// If `Some`, the automatic selection of the network interface only probes the interfaces whose name or description contains this pattern (case-insensitive). Ignored if [`SOEMOptionFull::ifname`](field@SOEMOptionFull::ifname) or [`SOEMOptionFull::mac_address`] is specified. The default is `None`.
+    pub ifname_filter: Option<String>,
+    /// The timeout of probing the network interfaces in the automatic selection. If any of the probed interfaces does not respond within this timeout, the selection fails because AUTD devices on it cannot be ruled out. The default is 5s.
+    pub lookup_timeout: Duration,
     /// The interval to check the state. The default is 100ms.
     pub state_check_interval: Duration,
     /// The cycle of the sync0 signal. The value must be a multiple of [`EC_CYCLE_TIME_BASE`] and not be zero. The default is 1ms.
@@ -39,6 +55,27 @@ pub struct SOEMOptionFull {
     pub sync_timeout: Duration,
     /// CPU affinity for the EtherCAT thread. The default is `None`, which means no affinity is set.
     pub affinity: Option<core_affinity::CoreId>,
+    /// If `true`, receiving fails when the inputs of any device were not refreshed in the latest cycle, i.e., the working counter did not match the expected value. The default is `false`.
+    pub reject_stale_input: bool,
+    /// The policy for the deadline misses of the EtherCAT thread.
+    pub deadline_miss_policy: DeadlineMissPolicy,
+    /// The process data group of each device, indexed by the device index. Devices in the same group share an IOMap region and a working counter check, so a fault in one group does not mark the inputs of the other groups as stale. The group index must be less than [`SOEMOptionFull::MAX_GROUPS`]. If `None`, all devices belong to a single group. The default is `None`.
+    ///
+    /// With multiple groups, the frames of the groups are sent and received one after another within each cycle instead of being received in the next cycle, so [`SOEMOptionFull::send_cycle`] must be long enough for a round trip of all of them.
+    pub device_groups: Option<Vec<usize>>,
+    /// If `true`, slaves other than AUTD devices are allowed on the bus. They are not mapped to the geometry, always belong to the first group, and their process data is accessible via [`SOEM::read_foreign_inputs`] and [`SOEM::write_foreign_outputs`]. [`SOEMOptionFull::FOREIGN_SLAVE_IOMAP_SIZE`] bytes of the IOMap are reserved for the process data of each of them. The default is `false`.
+    ///
+    /// [`SOEM::read_foreign_inputs`]: crate::link_soem::SOEM::read_foreign_inputs
+    /// [`SOEM::write_foreign_outputs`]: crate::link_soem::SOEM::write_foreign_outputs
+    pub allow_foreign_slaves: bool,
+    /// The identifiers of the AUTD devices in the order of the geometry. If specified, the `i`-th device in the geometry is mapped to the device identified by the `i`-th identifier regardless of its position on the bus, and all AUTD devices on the bus must be listed. If `None`, the devices are mapped in the order on the bus. The default is `None`.
+    pub device_ids: Option<Vec<DeviceId>>,
+    /// The timeout of each state transition on closing. The slaves are brought down in the order of OP, SAFE_OP, PRE_OP and INIT. The default is 2s.
+    pub shutdown_timeout: Duration,
+    /// If `Some`, closing waits up to this timeout until all queued frames have been sent before shutting down the slaves. The default is `None`.
+    pub flush_on_close: Option<Duration>,
+    /// The policy for retrying to open the link on transient failures.
+    pub retry_policy: RetryPolicy,
 }
 
 impl Default for SOEMOptionFull {
@@ -48,6 +85,14 @@ impl Default for SOEMOptionFull {
 }
 
 impl SOEMOptionFull {
+    /// The maximum number of process data groups, which is limited by `EC_MAXGROUP` of SOEM.
+    ///
+    /// SOEM reserves the group 0 for all slaves, so one less than `EC_MAXGROUP` groups are available.
+    pub const MAX_GROUPS: usize = EC_MAXGROUP as usize - 1;
+
+    /// The size of the IOMap reserved for each slave other than AUTD devices.
+    pub const FOREIGN_SLAVE_IOMAP_SIZE: usize = IOMap::FOREIGN_SLAVE_SIZE;
+
     pub(crate) fn validate(&self) -> Result<(), SOEMError> {
         if self.sync0_cycle.is_zero()
             || !self
@@ -65,14 +110,38 @@ impl SOEMOptionFull {
         {
             return Err(SOEMError::InvalidCycle(self.send_cycle));
         }
+        if let Some(&group) = self
+            .device_groups
+            .iter()
+            .flatten()
+            .find(|&&group| group >= Self::MAX_GROUPS)
+        {
+            return Err(SOEMError::InvalidGroup(group, Self::MAX_GROUPS));
+        }
         Ok(())
     }
 
-    pub(crate) fn ifname(&self) -> Result<CString, SOEMError> {
+    pub(crate) fn ifname(&self, expected_devices: Option<usize>) -> Result<CString, SOEMError> {
+        if let (None, Some(mac_address)) = (&self.ifname, self.mac_address) {
+            let adapter = EthernetAdapters::new()
+                .into_iter()
+                .find(|adapter| adapter.mac_address() == Some(mac_address))
+                .ok_or(SOEMError::AdapterNotFound(mac_address))?;
+            tracing::info!("Found {} with MAC address {}.", adapter, mac_address);
+            return CString::new(adapter.name())
+                .map_err(|_| SOEMError::InvalidInterfaceName(adapter.name().to_owned()));
+        }
         self.ifname.as_ref().map_or_else(
             || {
                 tracing::info!("No interface name is specified. Looking for AUTD device...");
-                let ifname = crate::inner::utils::lookup_autd()?;
+                // Probing fails silently on all interfaces without the permission.
+                crate::inner::preflight::check_raw_socket()?;
+                let ifname = crate::inner::interface_candidate::lookup_autd(
+                    self.allow_foreign_slaves,
+                    expected_devices,
+                    self.ifname_filter.as_deref(),
+                    self.lookup_timeout,
+                )?;
                 tracing::info!("Found AUTD device on {:?}.", ifname);
                 Ok(ifname)
             },
@@ -8,8 +8,15 @@
 //
 // You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
 
+mod deadline_miss;
+mod device_id;
 mod full;
+mod retry;
 mod simple;
 
+pub(crate) use deadline_miss::DeadlineMissCounter;
+pub use deadline_miss::{DeadlineMissAction, DeadlineMissPolicy};
+pub use device_id::DeviceId;
 pub use full::SOEMOptionFull;
+pub use retry::RetryPolicy;
 pub use simple::SOEMOption;
@@ -0,0 +1,69 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{num::NonZeroUsize, time::Duration};
+
+/// A policy for retrying to open the link.
+///
+/// Opening right after the devices are powered on may fail, e.g., because not all devices are found yet or the DC system time is not synchronized in time.
+/// Such transient failures are retried up to [`RetryPolicy::max_attempts`] times, and each failed attempt is reported to the status handler as [`Status::OpenFailed`].
+///
+/// [`Status::OpenFailed`]: crate::Status::OpenFailed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts including the first one. The default is 1, which means opening is not retried.
+    pub max_attempts: NonZeroUsize,
+    /// The delay before the first retry. The delay is doubled for each subsequent retry. The default is 500ms.
+    pub backoff: Duration,
+    /// The upper limit of the delay between retries. The default is 5s.
+    pub max_backoff: Duration,
+    /// The synchronization timeout of each attempt. If `Some`, it overrides [`SOEMOptionFull::sync_timeout`] so that a stuck attempt can be given up early. The default is `None`.
+    ///
+    /// [`SOEMOptionFull::sync_timeout`]: crate::SOEMOptionFull::sync_timeout
+    pub sync_timeout: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: NonZeroUsize::MIN,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(5),
+            sync_timeout: None,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub(crate) fn backoff(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        let policy = RetryPolicy {
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(3),
+            ..Default::default()
+        };
+        assert_eq!(Duration::from_millis(500), policy.backoff(0));
+        assert_eq!(Duration::from_secs(1), policy.backoff(1));
+        assert_eq!(Duration::from_secs(2), policy.backoff(2));
+        assert_eq!(Duration::from_secs(3), policy.backoff(3));
+        assert_eq!(Duration::from_secs(3), policy.backoff(100));
+    }
+}
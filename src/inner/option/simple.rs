@@ -50,6 +50,9 @@ impl From<SOEMOption> for SOEMOptionFull {
         Self {
             buf_size: NonZeroUsize::new(16).unwrap(),
             ifname: value.ifname,
+            mac_address: None,
+            ifname_filter: None,
+            lookup_timeout: Duration::from_secs(5),
             state_check_interval: value.state_check_interval,
             sync0_cycle: value.sync0_cycle,
             send_cycle: value.sync0_cycle,
@@ -72,6 +75,14 @@ impl From<SOEMOption> for SOEMOptionFull {
             sync_tolerance: value.sync_tolerance,
             sync_timeout: value.sync_timeout,
             affinity: None,
+            reject_stale_input: false,
+            deadline_miss_policy: Default::default(),
+            device_groups: None,
+            allow_foreign_slaves: false,
+            device_ids: None,
+            shutdown_timeout: Duration::from_secs(2),
+            flush_on_close: None,
+            retry_policy: Default::default(),
         }
     }
 }
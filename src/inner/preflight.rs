@@ -0,0 +1,75 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use crate::error::SOEMError;
+
+/// Checks if the link can be opened on the network interface `ifname`.
+///
+/// This detects the lack of the permission to open a raw socket ([`SOEMError::PermissionDenied`]), the interface which does not exist ([`SOEMError::InterfaceNotFound`]) or is down ([`SOEMError::InterfaceDown`]), and the interface on which no link is detected ([`SOEMError::NoCarrier`]). The same check is performed on opening the link.
+///
+/// This check is only supported on Linux, and always succeeds on other platforms.
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+pub fn preflight_check(ifname: &str) -> Result<(), SOEMError> {
+    check_raw_socket()?;
+    #[cfg(target_os = "linux")]
+    {
+        use super::{EthernetAdapter, OperState};
+
+        if !std::path::Path::new("/sys/class/net").join(ifname).exists() {
+            return Err(SOEMError::InterfaceNotFound(ifname.to_owned()));
+        }
+        let adapter = EthernetAdapter::new(ifname.to_owned(), String::new());
+        tracing::debug!(
+            "{}: operstate={:?}, carrier={:?}",
+            ifname,
+            adapter.oper_state(),
+            adapter.carrier()
+        );
+        if matches!(
+            adapter.oper_state(),
+            Some(OperState::Down | OperState::NotPresent)
+        ) && adapter.carrier().is_none()
+        {
+            return Err(SOEMError::InterfaceDown(ifname.to_owned()));
+        }
+        if adapter.carrier() == Some(false)
+            || adapter.oper_state() == Some(OperState::LowerLayerDown)
+        {
+            return Err(SOEMError::NoCarrier(ifname.to_owned()));
+        }
+    }
+    Ok(())
+}
+
+/// Checks the permission to open a raw socket, which SOEM requires.
+#[cfg(target_os = "linux")]
+pub(crate) fn check_raw_socket() -> Result<(), SOEMError> {
+    let fd = unsafe {
+        libc::socket(
+            libc::AF_PACKET,
+            libc::SOCK_RAW,
+            (libc::ETH_P_ALL as u16).to_be() as _,
+        )
+    };
+    if fd < 0 {
+        let err = std::io::Error::last_os_error();
+        return match err.raw_os_error() {
+            Some(libc::EPERM | libc::EACCES) => Err(SOEMError::PermissionDenied),
+            _ => Err(SOEMError::Io(err)),
+        };
+    }
+    unsafe { libc::close(fd) };
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub(crate) fn check_raw_socket() -> Result<(), SOEMError> {
+    Ok(())
+}
@@ -0,0 +1,404 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::path::Path;
+
+use crate::inner::{EthernetAdapters, SOEMOptionFull};
+
+/// The interrupt coalescing setting of a network interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Coalescing {
+    /// The delay of the RX interrupt in microseconds.
+    pub rx_usecs: u32,
+    /// The maximum number of frames received before the RX interrupt.
+    pub rx_max_frames: u32,
+    /// Whether the adaptive RX coalescing is enabled.
+    pub adaptive_rx: bool,
+}
+
+/// An interrupt of a network interface.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NicIrq {
+    /// The IRQ number.
+    pub irq: u32,
+    /// The name of the interrupt in `/proc/interrupts`.
+    pub name: String,
+    /// The CPUs which may serve the interrupt.
+    pub affinity: Vec<usize>,
+}
+
+/// A report on the readiness of the host for the real-time communication.
+///
+/// The deadline misses of the EtherCAT thread are usually caused by the host configuration. [`RtReadinessReport::recommendations`] lists what is worth changing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RtReadinessReport {
+    /// The value of `/proc/sys/kernel/sched_rt_runtime_us`. `-1` means the RT throttling is disabled.
+    pub sched_rt_runtime_us: Option<i64>,
+    /// The value of `/proc/sys/kernel/sched_rt_period_us`.
+    pub sched_rt_period_us: Option<i64>,
+    /// The CPUs isolated from the scheduler, e.g., by the `isolcpus` kernel parameter.
+    pub isolated_cpus: Vec<usize>,
+    /// The CPU frequency governor of each CPU.
+    pub governors: Vec<(usize, String)>,
+    /// The CPU for the EtherCAT thread specified by [`SOEMOptionFull::affinity`].
+    pub affinity: Option<usize>,
+    /// The network interface specified by [`SOEMOptionFull::ifname`](field@SOEMOptionFull::ifname) or [`SOEMOptionFull::mac_address`]. The interface related items are not inspected if `None`.
+    pub ifname: Option<String>,
+    /// The interrupts of the network interface.
+    pub irqs: Vec<NicIrq>,
+    /// The interrupt coalescing setting of the network interface. `None` if it cannot be read.
+    pub coalescing: Option<Coalescing>,
+    /// The error on applying the priority and the policy of [`SOEMOptionFull::thread_builder`]. `None` if they can be applied.
+    pub thread_priority_error: Option<String>,
+    /// The recommendations to improve the real-time performance.
+    pub recommendations: Vec<String>,
+}
+
+impl RtReadinessReport {
+    /// Inspects the current system for the link configured by `option`.
+    ///
+    /// A thread is spawned with [`SOEMOptionFull::thread_builder`] to check if its priority and policy can be applied.
+    pub fn inspect(option: &SOEMOptionFull) -> Self {
+        let ifname = option.ifname.clone().or_else(|| {
+            option.mac_address.and_then(|mac_address| {
+                EthernetAdapters::new()
+                    .into_iter()
+                    .find(|adapter| adapter.mac_address() == Some(mac_address))
+                    .map(|adapter| adapter.name().to_owned())
+            })
+        });
+
+        let governors = std::fs::read_dir("/sys/devices/system/cpu")
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let cpu = entry
+                    .file_name()
+                    .to_str()?
+                    .strip_prefix("cpu")?
+                    .parse()
+                    .ok()?;
+                let governor = read(&entry.path().join("cpufreq/scaling_governor"))?;
+                Some((cpu, governor))
+            })
+            .collect::<Vec<_>>();
+
+        let thread_priority_error = option
+            .thread_builder
+            .clone()
+            .spawn(|result| result.map_err(|e| e.to_string()))
+            .map_err(|e| e.to_string())
+            .and_then(|th| th.join().unwrap_or(Ok(())))
+            .err();
+
+        let mut report = Self {
+            sched_rt_runtime_us: read_parse("/proc/sys/kernel/sched_rt_runtime_us"),
+            sched_rt_period_us: read_parse("/proc/sys/kernel/sched_rt_period_us"),
+            isolated_cpus: read(Path::new("/sys/devices/system/cpu/isolated"))
+                .map(|s| parse_cpu_list(&s))
+                .unwrap_or_default(),
+            governors: {
+                let mut governors = governors;
+                governors.sort();
+                governors
+            },
+            affinity: option.affinity.map(|core| core.id),
+            irqs: ifname.as_deref().map(nic_irqs).unwrap_or_default(),
+            coalescing: ifname.as_deref().and_then(coalescing),
+            ifname,
+            thread_priority_error,
+            recommendations: Vec::new(),
+        };
+        report.recommendations = report.recommend();
+        report
+    }
+
+    /// Returns `true` if there is no recommendation.
+    pub fn is_ready(&self) -> bool {
+        self.recommendations.is_empty()
+    }
+
+    fn recommend(&self) -> Vec<String> {
+        let mut recommendations = Vec::new();
+
+        // Without RT throttling, a spinning real-time thread starves the other tasks on its CPU, so it is only safe on an isolated CPU.
+        let isolated = self
+            .affinity
+            .is_some_and(|cpu| self.isolated_cpus.contains(&cpu));
+        match self.sched_rt_runtime_us {
+            Some(runtime) if runtime >= 0 && isolated => recommendations.push(format!(
+                "RT throttling limits real-time tasks to {}us in every {}us. Disable it with `sudo sysctl kernel.sched_rt_runtime_us=-1`.",
+                runtime,
+                self.sched_rt_period_us.unwrap_or(1_000_000)
+            )),
+            Some(runtime) if runtime < 0 && !isolated => recommendations.push(
+                "RT throttling is disabled, but the EtherCAT thread is not pinned to an isolated CPU. A spinning real-time thread may hang the host. Pin it to an isolated CPU or enable RT throttling with `sudo sysctl kernel.sched_rt_runtime_us=950000`.".to_owned(),
+            ),
+            _ => {}
+        }
+
+        match self.affinity {
+            Some(cpu) if !self.isolated_cpus.contains(&cpu) => recommendations.push(format!(
+                "CPU {} for the EtherCAT thread is not isolated. Add it to the `isolcpus` kernel parameter.",
+                cpu
+            )),
+            Some(_) => {}
+            None if self.isolated_cpus.is_empty() => recommendations.push(
+                "No CPU is isolated. Isolate a CPU with the `isolcpus` kernel parameter and pin the EtherCAT thread to it with `SOEMOptionFull::affinity`.".to_owned(),
+            ),
+            None => recommendations.push(format!(
+                "CPUs {:?} are isolated, but the EtherCAT thread is not pinned. Set `SOEMOptionFull::affinity` to one of them.",
+                self.isolated_cpus
+            )),
+        }
+
+        let slow_cpus = self
+            .governors
+            .iter()
+            .filter(|(cpu, _)| self.affinity.is_none_or(|affinity| affinity == *cpu))
+            .filter(|(_, governor)| governor != "performance")
+            .map(|(cpu, _)| *cpu)
+            .collect::<Vec<_>>();
+        if !slow_cpus.is_empty() {
+            recommendations.push(format!(
+                "CPU frequency governor of CPUs {:?} is not `performance`. Set it with `sudo cpupower frequency-set -g performance`.",
+                slow_cpus
+            ));
+        }
+
+        if let Some(ifname) = &self.ifname {
+            if let Some(cpu) = self.affinity {
+                self.irqs
+                    .iter()
+                    .filter(|irq| irq.affinity.contains(&cpu))
+                    .for_each(|irq| {
+                        recommendations.push(format!(
+                            "IRQ {} ({}) of {} may be served on CPU {} running the EtherCAT thread. Move it to another CPU via `/proc/irq/{}/smp_affinity_list`.",
+                            irq.irq, irq.name, ifname, cpu, irq.irq
+                        ))
+                    });
+            }
+            if let Some(coalescing) = self.coalescing
+                && (coalescing.rx_usecs > 0 || coalescing.adaptive_rx)
+            {
+                recommendations.push(format!(
+                    "Interrupt coalescing on {} delays the reception (rx-usecs: {}, adaptive-rx: {}). Disable it with `sudo ethtool -C {} adaptive-rx off rx-usecs 0`.",
+                    ifname,
+                    coalescing.rx_usecs,
+                    if coalescing.adaptive_rx { "on" } else { "off" },
+                    ifname
+                ));
+            }
+        }
+
+        if let Some(err) = &self.thread_priority_error {
+            recommendations.push(format!(
+                "The priority and the policy of the EtherCAT thread cannot be applied ({}). Run as root, grant CAP_SYS_NICE, or raise `rtprio` in `/etc/security/limits.conf`.",
+                err
+            ));
+        }
+
+        recommendations
+    }
+}
+
+impl std::fmt::Display for RtReadinessReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_ready() {
+            return write!(f, "The host is ready for the real-time communication.");
+        }
+        write!(f, "Recommendations:")?;
+        self.recommendations
+            .iter()
+            .try_for_each(|recommendation| write!(f, "\n- {}", recommendation))
+    }
+}
+
+fn read(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_owned())
+}
+
+fn read_parse<T: std::str::FromStr>(path: &str) -> Option<T> {
+    read(Path::new(path))?.parse().ok()
+}
+
+fn parse_cpu_list(s: &str) -> Vec<usize> {
+    s.split(',')
+        .filter_map(|range| match range.trim().split_once('-') {
+            Some((start, end)) => Some(start.parse().ok()?..=end.parse().ok()?),
+            None => {
+                let cpu = range.trim().parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+fn parse_interrupts(s: &str) -> Vec<(u32, String)> {
+    s.lines()
+        .filter_map(|line| {
+            let (irq, rest) = line.trim_start().split_once(':')?;
+            let irq = irq.parse().ok()?;
+            let name = rest.split_whitespace().last()?;
+            Some((irq, name.to_owned()))
+        })
+        .collect()
+}
+
+fn nic_irqs(ifname: &str) -> Vec<NicIrq> {
+    let interrupts = read(Path::new("/proc/interrupts"))
+        .map(|s| parse_interrupts(&s))
+        .unwrap_or_default();
+    // Some drivers name the interrupts after the PCI device instead of the interface.
+    let msi_irqs = std::fs::read_dir(
+        Path::new("/sys/class/net")
+            .join(ifname)
+            .join("device/msi_irqs"),
+    )
+    .into_iter()
+    .flatten()
+    .flatten()
+    .filter_map(|entry| entry.file_name().to_str()?.parse().ok())
+    .collect::<Vec<u32>>();
+    interrupts
+        .into_iter()
+        .filter(|(irq, name)| {
+            name == ifname
+                || name
+                    .strip_prefix(ifname)
+                    .is_some_and(|suffix| suffix.starts_with('-'))
+                || msi_irqs.contains(irq)
+        })
+        .map(|(irq, name)| NicIrq {
+            irq,
+            affinity: read(Path::new(&format!("/proc/irq/{}/smp_affinity_list", irq)))
+                .map(|s| parse_cpu_list(&s))
+                .unwrap_or_default(),
+            name,
+        })
+        .collect()
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct EthtoolCoalesce {
+    cmd: u32,
+    rx_coalesce_usecs: u32,
+    rx_max_coalesced_frames: u32,
+    _reserved0: [u32; 7],
+    use_adaptive_rx_coalesce: u32,
+    _reserved1: [u32; 12],
+}
+
+fn coalescing(ifname: &str) -> Option<Coalescing> {
+    const ETHTOOL_GCOALESCE: u32 = 0x0000000e;
+
+    if ifname.len() >= libc::IFNAMSIZ {
+        return None;
+    }
+    let mut coalesce = EthtoolCoalesce {
+        cmd: ETHTOOL_GCOALESCE,
+        ..Default::default()
+    };
+    let mut ifr: libc::ifreq = unsafe { std::mem::zeroed() };
+    ifr.ifr_name
+        .iter_mut()
+        .zip(ifname.bytes())
+        .for_each(|(dst, src)| *dst = src as _);
+    ifr.ifr_ifru.ifru_data = &mut coalesce as *mut EthtoolCoalesce as *mut _;
+
+    let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_DGRAM, 0) };
+    if fd < 0 {
+        return None;
+    }
+    let res = unsafe { libc::ioctl(fd, libc::SIOCETHTOOL as _, &mut ifr) };
+    unsafe { libc::close(fd) };
+    (res >= 0).then_some(Coalescing {
+        rx_usecs: coalesce.rx_coalesce_usecs,
+        rx_max_frames: coalesce.rx_max_coalesced_frames,
+        adaptive_rx: coalesce.use_adaptive_rx_coalesce != 0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(Vec::<usize>::new(), parse_cpu_list(""));
+        assert_eq!(vec![3], parse_cpu_list("3"));
+        assert_eq!(vec![0, 1, 2, 3, 6], parse_cpu_list("0-3,6\n"));
+    }
+
+    #[test]
+    fn test_parse_interrupts() {
+        let interrupts = "           CPU0       CPU1
+  0:         20          0   IO-APIC    2-edge      timer
+ 45:     123456          0   PCI-MSI 524288-edge      eth0-TxRx-0
+NMI:          0          0   Non-maskable interrupts
+";
+        assert_eq!(
+            vec![(0, "timer".to_owned()), (45, "eth0-TxRx-0".to_owned())],
+            parse_interrupts(interrupts)
+        );
+    }
+
+    #[test]
+    fn test_recommend() {
+        let mut report = RtReadinessReport {
+            sched_rt_runtime_us: Some(-1),
+            sched_rt_period_us: Some(1_000_000),
+            isolated_cpus: vec![3],
+            governors: vec![(0, "powersave".to_owned()), (3, "performance".to_owned())],
+            affinity: Some(3),
+            ifname: Some("eth0".to_owned()),
+            irqs: vec![NicIrq {
+                irq: 45,
+                name: "eth0-TxRx-0".to_owned(),
+                affinity: vec![0, 1, 2],
+            }],
+            coalescing: Some(Coalescing {
+                rx_usecs: 0,
+                rx_max_frames: 1,
+                adaptive_rx: false,
+            }),
+            thread_priority_error: None,
+            recommendations: Vec::new(),
+        };
+        assert!(report.recommend().is_empty());
+
+        report.sched_rt_runtime_us = Some(950_000);
+        report.affinity = None;
+        report.irqs[0].affinity = vec![3];
+        report.coalescing = report.coalescing.map(|c| Coalescing { rx_usecs: 3, ..c });
+        report.thread_priority_error = Some("Operation not permitted".to_owned());
+        // Not pinned, governor of CPU 0, coalescing and the thread priority. The IRQ is not checked without the affinity, and RT throttling is kept without an isolated CPU.
+        assert_eq!(4, report.recommend().len());
+
+        report.sched_rt_runtime_us = Some(-1);
+        assert_eq!(5, report.recommend().len());
+        assert!(report.recommend()[0].contains("may hang the host"));
+
+        report.affinity = Some(3);
+        report.coalescing = None;
+        report.irqs.clear();
+        report.thread_priority_error = None;
+        assert!(report.recommend().is_empty());
+
+        report.sched_rt_runtime_us = Some(950_000);
+        assert_eq!(1, report.recommend().len());
+        assert!(report.recommend()[0].contains("sched_rt_runtime_us=-1"));
+    }
+}
@@ -0,0 +1,216 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::ffi::{CStr, c_char};
+
+use super::*;
+
+/// The kind of [`SlaveError`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlaveErrorKind {
+    /// SDO abort.
+    Sdo {
+        /// The SDO abort code.
+        abort_code: u32,
+    },
+    /// Emergency message.
+    Emergency {
+        /// The emergency error code.
+        error_code: u16,
+        /// The error register.
+        error_register: u8,
+        /// The manufacturer specific error data.
+        data: [u8; 5],
+    },
+    /// Packet error.
+    Packet {
+        /// The error code.
+        error_code: u16,
+    },
+    /// SDO information error.
+    SdoInfo {
+        /// The SDO abort code.
+        abort_code: u32,
+    },
+    /// FoE error.
+    Foe {
+        /// The FoE error code.
+        error_code: u32,
+    },
+    /// FoE buffer is too small.
+    FoeBufferTooSmall,
+    /// FoE packet number mismatch.
+    FoePacketNumber,
+    /// SoE error.
+    Soe {
+        /// The SoE error code.
+        error_code: u16,
+    },
+    /// Mailbox error.
+    Mailbox {
+        /// The mailbox error code.
+        error_code: u16,
+    },
+    /// FoE file is not found.
+    FoeFileNotFound,
+    /// EoE received invalid data.
+    EoeInvalidRxData,
+    /// Unknown error type.
+    Unknown(u32),
+}
+
+/// An error reported by a slave, e.g., an SDO abort or an emergency message, which SOEM accumulates in its error list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlaveError {
+    /// The position of the slave on the bus starting from 1.
+    pub slave: u16,
+    /// The CoE object index.
+    pub index: u16,
+    /// The CoE object subindex.
+    pub sub_index: u8,
+    /// The kind of the error.
+    pub kind: SlaveErrorKind,
+}
+
+impl From<&ec_errort> for SlaveError {
+    #[allow(non_upper_case_globals)]
+    fn from(err: &ec_errort) -> Self {
+        let abort_code = unsafe { err.__bindgen_anon_1.AbortCode } as u32;
+        let emergency = unsafe { err.__bindgen_anon_1.__bindgen_anon_1 };
+        let error_code = emergency.ErrorCode;
+        let kind = match err.Etype {
+            ec_err_type_EC_ERR_TYPE_SDO_ERROR => SlaveErrorKind::Sdo { abort_code },
+            ec_err_type_EC_ERR_TYPE_EMERGENCY => {
+                let [w1_0, w1_1] = emergency.w1.to_le_bytes();
+                let [w2_0, w2_1] = emergency.w2.to_le_bytes();
+                SlaveErrorKind::Emergency {
+                    error_code,
+                    error_register: emergency.ErrorReg,
+                    data: [emergency.b1, w1_0, w1_1, w2_0, w2_1],
+                }
+            }
+            ec_err_type_EC_ERR_TYPE_PACKET_ERROR => SlaveErrorKind::Packet { error_code },
+            ec_err_type_EC_ERR_TYPE_SDOINFO_ERROR => SlaveErrorKind::SdoInfo { abort_code },
+            ec_err_type_EC_ERR_TYPE_FOE_ERROR => SlaveErrorKind::Foe {
+                error_code: abort_code,
+            },
+            ec_err_type_EC_ERR_TYPE_FOE_BUF2SMALL => SlaveErrorKind::FoeBufferTooSmall,
+            ec_err_type_EC_ERR_TYPE_FOE_PACKETNUMBER => SlaveErrorKind::FoePacketNumber,
+            ec_err_type_EC_ERR_TYPE_SOE_ERROR => SlaveErrorKind::Soe { error_code },
+            ec_err_type_EC_ERR_TYPE_MBX_ERROR => SlaveErrorKind::Mailbox { error_code },
+            ec_err_type_EC_ERR_TYPE_FOE_FILE_NOTFOUND => SlaveErrorKind::FoeFileNotFound,
+            ec_err_type_EC_ERR_TYPE_EOE_INVALID_RX_DATA => SlaveErrorKind::EoeInvalidRxData,
+            ty => SlaveErrorKind::Unknown(ty as _),
+        };
+        Self {
+            slave: err.Slave,
+            index: err.Index,
+            sub_index: err.SubIdx,
+            kind,
+        }
+    }
+}
+
+fn to_string(s: *const c_char) -> String {
+    unsafe { CStr::from_ptr(s) }.to_string_lossy().into_owned()
+}
+
+impl std::fmt::Display for SlaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Slave[{}] ", self.slave)?;
+        match self.kind {
+            SlaveErrorKind::Sdo { abort_code } => write!(
+                f,
+                "SDO error at {:#06X}:{:02X}: {} ({:#010X})",
+                self.index,
+                self.sub_index,
+                to_string(unsafe { ec_sdoerror2string(abort_code) }),
+                abort_code
+            ),
+            SlaveErrorKind::Emergency {
+                error_code,
+                error_register,
+                data,
+            } => write!(
+                f,
+                "emergency: error code {:#06X}, error register {:#04X}, data {:02X?}",
+                error_code, error_register, data
+            ),
+            SlaveErrorKind::Packet { error_code } => write!(
+                f,
+                "packet error at {:#06X}:{:02X}: error code {}",
+                self.index, self.sub_index, error_code
+            ),
+            SlaveErrorKind::SdoInfo { abort_code } => write!(
+                f,
+                "SDO information error at {:#06X}:{:02X}: {} ({:#010X})",
+                self.index,
+                self.sub_index,
+                to_string(unsafe { ec_sdoerror2string(abort_code) }),
+                abort_code
+            ),
+            SlaveErrorKind::Foe { error_code } => {
+                write!(f, "FoE error: error code {:#010X}", error_code)
+            }
+            SlaveErrorKind::FoeBufferTooSmall => write!(f, "FoE buffer is too small"),
+            SlaveErrorKind::FoePacketNumber => write!(f, "FoE packet number mismatch"),
+            SlaveErrorKind::Soe { error_code } => write!(
+                f,
+                "SoE error: {} ({:#06X})",
+                to_string(unsafe { ec_soeerror2string(error_code) }),
+                error_code
+            ),
+            SlaveErrorKind::Mailbox { error_code } => write!(
+                f,
+                "mailbox error: {} ({:#06X})",
+                to_string(unsafe { ec_mbxerror2string(error_code) }),
+                error_code
+            ),
+            SlaveErrorKind::FoeFileNotFound => write!(f, "FoE file is not found"),
+            SlaveErrorKind::EoeInvalidRxData => write!(f, "EoE received invalid data"),
+            SlaveErrorKind::Unknown(ty) => write!(f, "unknown error type {}", ty),
+        }
+    }
+}
+
+impl std::error::Error for SlaveError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slave_error_from_emergency() {
+        let mut err: ec_errort = unsafe { std::mem::zeroed() };
+        err.Slave = 2;
+        err.Etype = ec_err_type_EC_ERR_TYPE_EMERGENCY;
+        err.__bindgen_anon_1.__bindgen_anon_1.ErrorCode = 0x8130;
+        err.__bindgen_anon_1.__bindgen_anon_1.ErrorReg = 0x11;
+        err.__bindgen_anon_1.__bindgen_anon_1.b1 = 0x01;
+        err.__bindgen_anon_1.__bindgen_anon_1.w1 = 0x0302;
+        err.__bindgen_anon_1.__bindgen_anon_1.w2 = 0x0504;
+        assert_eq!(
+            SlaveError {
+                slave: 2,
+                index: 0,
+                sub_index: 0,
+                kind: SlaveErrorKind::Emergency {
+                    error_code: 0x8130,
+                    error_register: 0x11,
+                    data: [0x01, 0x02, 0x03, 0x04, 0x05],
+                },
+            },
+            SlaveError::from(&err)
+        );
+
+        err.Etype = 0xFF;
+        assert_eq!(SlaveErrorKind::Unknown(0xFF), SlaveError::from(&err).kind);
+    }
+}
@@ -179,7 +179,7 @@ pub const EC_LOGGROUPOFFSET: u32 = 16;
 pub const EC_MAXELIST: u32 = 64;
 pub const EC_MAXNAME: u32 = 40;
 pub const EC_MAXSLAVE: u32 = 200;
-pub const EC_MAXGROUP: u32 = 2;
+pub const EC_MAXGROUP: u32 = 8;
 pub const EC_MAXIOSEGMENTS: u32 = 64;
 pub const EC_MAXMBX: u32 = 1486;
 pub const EC_MBXPOOLSIZE: u32 = 32;
@@ -89928,7 +89928,7 @@ pub struct ecx_context {
     #[doc = " number of slaves found in configuration"]
     pub slavecount: ::std::os::raw::c_int,
     #[doc = " list of groups"]
-    pub grouplist: [ec_groupt; 2usize],
+    pub grouplist: [ec_groupt; 8usize],
     #[doc = " ecaterror state"]
     pub ecaterror: boolean,
     #[doc = " last DC time from slaves"]
@@ -89984,7 +89984,7 @@ pub struct ecx_context {
 }
 #[allow(clippy::unnecessary_operation, clippy::identity_op)]
 const _: () = {
-    ["Size of ecx_context"][::std::mem::size_of::<ecx_context>() - 204576usize];
+    ["Size of ecx_context"][::std::mem::size_of::<ecx_context>() - 213312usize];
     ["Alignment of ecx_context"][::std::mem::align_of::<ecx_context>() - 8usize];
     ["Offset of field: ecx_context::port"][::std::mem::offset_of!(ecx_context, port) - 0usize];
     ["Offset of field: ecx_context::slavelist"]
@@ -89994,44 +89994,44 @@ const _: () = {
     ["Offset of field: ecx_context::grouplist"]
         [::std::mem::offset_of!(ecx_context, grouplist) - 144832usize];
     ["Offset of field: ecx_context::ecaterror"]
-        [::std::mem::offset_of!(ecx_context, ecaterror) - 147744usize];
+        [::std::mem::offset_of!(ecx_context, ecaterror) - 156480usize];
     ["Offset of field: ecx_context::DCtime"]
-        [::std::mem::offset_of!(ecx_context, DCtime) - 147752usize];
+        [::std::mem::offset_of!(ecx_context, DCtime) - 156488usize];
     ["Offset of field: ecx_context::esibuf"]
-        [::std::mem::offset_of!(ecx_context, esibuf) - 147760usize];
+        [::std::mem::offset_of!(ecx_context, esibuf) - 156496usize];
     ["Offset of field: ecx_context::esimap"]
-        [::std::mem::offset_of!(ecx_context, esimap) - 151856usize];
+        [::std::mem::offset_of!(ecx_context, esimap) - 160592usize];
     ["Offset of field: ecx_context::esislave"]
-        [::std::mem::offset_of!(ecx_context, esislave) - 152368usize];
+        [::std::mem::offset_of!(ecx_context, esislave) - 161104usize];
     ["Offset of field: ecx_context::elist"]
-        [::std::mem::offset_of!(ecx_context, elist) - 152376usize];
+        [::std::mem::offset_of!(ecx_context, elist) - 161112usize];
     ["Offset of field: ecx_context::idxstack"]
-        [::std::mem::offset_of!(ecx_context, idxstack) - 154984usize];
+        [::std::mem::offset_of!(ecx_context, idxstack) - 163720usize];
     ["Offset of field: ecx_context::SMcommtype"]
-        [::std::mem::offset_of!(ecx_context, SMcommtype) - 155216usize];
+        [::std::mem::offset_of!(ecx_context, SMcommtype) - 163952usize];
     ["Offset of field: ecx_context::PDOassign"]
-        [::std::mem::offset_of!(ecx_context, PDOassign) - 155226usize];
+        [::std::mem::offset_of!(ecx_context, PDOassign) - 163962usize];
     ["Offset of field: ecx_context::PDOdesc"]
-        [::std::mem::offset_of!(ecx_context, PDOdesc) - 155740usize];
+        [::std::mem::offset_of!(ecx_context, PDOdesc) - 164476usize];
     ["Offset of field: ecx_context::eepSM"]
-        [::std::mem::offset_of!(ecx_context, eepSM) - 156766usize];
+        [::std::mem::offset_of!(ecx_context, eepSM) - 165502usize];
     ["Offset of field: ecx_context::eepFMMU"]
-        [::std::mem::offset_of!(ecx_context, eepFMMU) - 156778usize];
+        [::std::mem::offset_of!(ecx_context, eepFMMU) - 165514usize];
     ["Offset of field: ecx_context::mbxpool"]
-        [::std::mem::offset_of!(ecx_context, mbxpool) - 156792usize];
-    ["Offset of field: ecx_context::ENI"][::std::mem::offset_of!(ecx_context, ENI) - 204528usize];
+        [::std::mem::offset_of!(ecx_context, mbxpool) - 165528usize];
+    ["Offset of field: ecx_context::ENI"][::std::mem::offset_of!(ecx_context, ENI) - 213264usize];
     ["Offset of field: ecx_context::FOEhook"]
-        [::std::mem::offset_of!(ecx_context, FOEhook) - 204536usize];
+        [::std::mem::offset_of!(ecx_context, FOEhook) - 213272usize];
     ["Offset of field: ecx_context::EOEhook"]
-        [::std::mem::offset_of!(ecx_context, EOEhook) - 204544usize];
+        [::std::mem::offset_of!(ecx_context, EOEhook) - 213280usize];
     ["Offset of field: ecx_context::manualstatechange"]
-        [::std::mem::offset_of!(ecx_context, manualstatechange) - 204552usize];
+        [::std::mem::offset_of!(ecx_context, manualstatechange) - 213288usize];
     ["Offset of field: ecx_context::userdata"]
-        [::std::mem::offset_of!(ecx_context, userdata) - 204560usize];
+        [::std::mem::offset_of!(ecx_context, userdata) - 213296usize];
     ["Offset of field: ecx_context::overlappedMode"]
-        [::std::mem::offset_of!(ecx_context, overlappedMode) - 204568usize];
+        [::std::mem::offset_of!(ecx_context, overlappedMode) - 213304usize];
     ["Offset of field: ecx_context::packedMode"]
-        [::std::mem::offset_of!(ecx_context, packedMode) - 204569usize];
+        [::std::mem::offset_of!(ecx_context, packedMode) - 213305usize];
 };
 unsafe extern "C" {
     pub fn ec_find_adapters() -> *mut ec_adaptert;
@@ -10,32 +10,43 @@
 
 use super::*;
 
+/// The EtherCAT state of a slave.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct State(ec_state);
 
 impl State {
+    /// No valid state.
     pub const NONE: Self = Self(ec_state_EC_STATE_NONE);
+    /// Init state.
     pub const INIT: Self = Self(ec_state_EC_STATE_INIT);
+    /// Pre-operational state.
     pub const PRE_OP: Self = Self(ec_state_EC_STATE_PRE_OP);
+    /// Safe-operational state.
     pub const SAFE_OP: Self = Self(ec_state_EC_STATE_SAFE_OP);
+    /// Operational state.
     pub const OPERATIONAL: Self = Self(ec_state_EC_STATE_OPERATIONAL);
 
+    /// The raw value of the state.
     pub const fn state(self) -> u16 {
         self.0 as _
     }
 
+    /// Returns `true` if the state is not valid.
     pub fn is_none(self) -> bool {
         self.0 == ec_state_EC_STATE_NONE
     }
 
+    /// Returns `true` if the state is valid.
     pub fn is_some(self) -> bool {
         self.0 > ec_state_EC_STATE_NONE
     }
 
+    /// Returns `true` if the state is safe-operational regardless of the error flag.
     pub fn is_safe_op(self) -> bool {
         (self.0 & !ec_state_EC_STATE_ERROR) == ec_state_EC_STATE_SAFE_OP
     }
 
+    /// Returns `true` if the error flag is set.
     pub fn is_error(self) -> bool {
         (self.0 & ec_state_EC_STATE_ERROR) != 0
     }
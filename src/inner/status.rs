@@ -8,12 +8,14 @@
 //
 // You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
 
+use super::{AlStatusCode, SlaveError};
+
 #[derive(Debug, Clone, PartialEq)]
 #[repr(u8)]
 /// The status of the EtherCAT slave.
 pub enum Status {
-    /// The slave is in SAFE_OP + ERROR.
-    Error = 0,
+    /// The slave is in SAFE_OP + ERROR with the AL status code.
+    Error(AlStatusCode) = 0,
     /// The slave is lost.
     Lost = 1,
     /// The slave is in SAFE_OP.
@@ -22,16 +24,57 @@ pub enum Status {
     Recovered = 3,
     /// All slaves resumed OPERATIONAL.
     Resumed = 4,
+    /// The EtherCAT thread missed the deadline of the cycle more than the threshold of [`DeadlineMissPolicy`].
+    ///
+    /// [`DeadlineMissPolicy`]: crate::DeadlineMissPolicy
+    DeadlineMissed {
+        /// The number of consecutive deadline misses.
+        consecutive: usize,
+        /// The total number of deadline misses since the link was opened.
+        total: usize,
+    } = 5,
+    /// The slave reported an error, e.g., an SDO abort or an emergency message.
+    SlaveError(SlaveError) = 6,
+    /// An attempt to open the link failed. See [`RetryPolicy`].
+    ///
+    /// [`RetryPolicy`]: crate::RetryPolicy
+    OpenFailed {
+        /// The number of the attempt starting from 1.
+        attempt: usize,
+        /// The error of the attempt.
+        error: String,
+        /// Whether opening is retried.
+        retry: bool,
+    } = 7,
 }
 
 impl std::fmt::Display for Status {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Status::Error => write!(f, "slave is in SAFE_OP + ERROR, attempting ack"),
+            Status::Error(code) => {
+                write!(f, "slave is in SAFE_OP + ERROR ({}), attempting ack", code)
+            }
             Status::Lost => write!(f, "slave is lost"),
             Status::StateChanged => write!(f, "slave is in SAFE_OP, change to OPERATIONAL"),
             Status::Recovered => write!(f, "slave is recovered"),
             Status::Resumed => write!(f, "all slaves resumed OPERATIONAL"),
+            Status::DeadlineMissed { consecutive, total } => write!(
+                f,
+                "EtherCAT thread missed the deadline {} times in a row ({} times in total)",
+                consecutive, total
+            ),
+            Status::SlaveError(err) => write!(f, "{}", err),
+            Status::OpenFailed {
+                attempt,
+                error,
+                retry,
+            } => write!(
+                f,
+                "attempt {} to open the link failed{}: {}",
+                attempt,
+                if *retry { ", retrying" } else { "" },
+                error
+            ),
         }
     }
 }
@@ -8,15 +8,15 @@
 //
 // You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
 
-use std::ffi::{CStr, CString};
+use std::ffi::CStr;
 
-use crate::{
-    EthernetAdapters,
-    {
-        error::SOEMError,
-        inner::{Context, ec_slave},
-    },
-};
+use crate::inner::ec_slave;
+
+pub fn slave_name(slave: &ec_slave) -> String {
+    unsafe { CStr::from_ptr(slave.name.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
 
 pub fn is_autd3(slave: &ec_slave) -> bool {
     const AUTD_NAME: &CStr = c"AUTD";
@@ -24,34 +24,3 @@ pub fn is_autd3(slave: &ec_slave) -> bool {
     tracing::trace!("Slave name: {:?}", name);
     name == AUTD_NAME
 }
-
-pub fn lookup_autd() -> Result<CString, SOEMError> {
-    let adapters = EthernetAdapters::new();
-    tracing::debug!("Found {} network adapters.", adapters.len());
-    adapters
-        .into_iter()
-        .find_map(|adapter| {
-            let ifname = match CString::new(adapter.name().to_owned()) {
-                Ok(ifname) => ifname,
-                Err(_) => return None,
-            };
-            tracing::debug!("Searching AUTD device on {}.", adapter.name());
-            let ctx = Context::new();
-            match ctx.init(ifname.clone()) {
-                Ok(_) => {
-                    if let Some(wc) = ctx.config_init() {
-                        tracing::trace!("Found {} slaves on {}.", wc, adapter.name());
-                        ctx.slaves().all(is_autd3).then_some(ifname)
-                    } else {
-                        tracing::trace!("No slave found on {}.", adapter.name());
-                        None
-                    }
-                }
-                Err(_) => {
-                    tracing::trace!("Failed to initialize SOEM on {}.", adapter.name());
-                    None
-                }
-            }
-        })
-        .ok_or(SOEMError::NoDeviceFound)
-}
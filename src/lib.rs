@@ -17,9 +17,23 @@
 
 mod error;
 mod inner;
+mod link_multi_soem;
 mod link_soem;
+mod sleeper;
 
 pub use core_affinity;
-pub use inner::{EthernetAdapters, SOEMOption, SOEMOptionFull, Status};
+pub use error::SOEMError;
+pub use inner::{
+    AlStatusCode, DcClockMapping, DeadlineMissAction, DeadlineMissPolicy, DeviceId, DeviceInfo,
+    Duplex, EthernetAdapter, EthernetAdapters, ForeignSlave, InterfaceCandidate, MacAddress,
+    MailboxProtocols, OpenReport, OpenStage, OperState, RetryPolicy, RxMetadata, SOEMOption,
+    SOEMOptionFull, SlaveError, SlaveErrorKind, SlaveReport, State, Status, preflight_check,
+};
+#[cfg(target_os = "linux")]
+pub use inner::{Coalescing, NicIrq, RtReadinessReport};
+pub use link_multi_soem::MultiSOEM;
 pub use link_soem::SOEM;
+pub use sleeper::{Calibration, DeadlineSleeper, HybridSleeper, ThreadSleeper, calibrate};
+#[cfg(target_os = "linux")]
+pub use sleeper::{ClockNanosleepSleeper, TimerFdSleeper};
 pub use thread_priority;
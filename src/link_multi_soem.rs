@@ -0,0 +1,399 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{ops::Range, sync::Arc};
+
+use autd3_core::{
+    geometry::Geometry,
+    link::{AsyncLink, Link, LinkError, RxMessage, TxMessage},
+    sleep::Sleeper,
+};
+
+use crate::{
+    error::SOEMError,
+    inner::{DcClockMapping, OpenReport, SOEMHandler, SOEMOptionFull, SlaveError},
+    link_soem::{SpinSleeper, receive_validated, record_error},
+    sleeper::{CycleSleeper, DeadlineSleeper},
+};
+
+use super::Status;
+
+/// A [`Link`] which drives several network interfaces, each with its own SOEM master, as a single link.
+///
+/// The devices are mapped to the geometry in the order of the options, and in the order on the bus within each interface.
+/// The EtherCAT threads of all interfaces start their cycles aligned to the host UTC time, so the options must have the same [`SOEMOptionFull::send_cycle`] and [`SOEMOptionFull::sync0_cycle`].
+///
+/// Note that the devices on different interfaces are NOT synchronized by the distributed clocks.
+/// Each interface has its own reference clock, and the EtherCAT thread of each interface follows its own reference clock, so the SYNC0 events of the interfaces drift apart from each other over time.
+/// The drift can be monitored with [`MultiSOEM::dc_clocks`].
+///
+/// The index passed to the error handler and [`SlaveError::slave`] of [`Status::SlaveError`] are the position of the slave counted through all interfaces, and 0 is passed for the events not specific to a slave.
+pub struct MultiSOEM<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper> {
+    option: Option<(F, Vec<SOEMOptionFull>, CycleSleeper<S>)>,
+    handlers: Vec<(SOEMHandler, Range<usize>)>,
+    buffers: Vec<Vec<TxMessage>>,
+    shutdown_frame: Option<Vec<TxMessage>>,
+    last_error: Option<SOEMError>,
+}
+
+impl<F: Fn(u16, Status) + Send + Sync + 'static> MultiSOEM<F, SpinSleeper> {
+    /// Creates a new [`MultiSOEM`].
+    pub fn new<O: Into<SOEMOptionFull>>(
+        err_handler: F,
+        options: impl IntoIterator<Item = O>,
+    ) -> MultiSOEM<F, SpinSleeper> {
+        MultiSOEM::with_sleeper(err_handler, options, SpinSleeper::default())
+    }
+}
+
+impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Clone> MultiSOEM<F, S> {
+    /// Creates a new [`MultiSOEM`] with a sleeper. Each EtherCAT thread uses its own clone of `sleeper`.
+    pub fn with_sleeper<O: Into<SOEMOptionFull>>(
+        err_handler: F,
+        options: impl IntoIterator<Item = O>,
+        sleeper: S,
+    ) -> MultiSOEM<F, S> {
+        MultiSOEM {
+            option: Some((
+                err_handler,
+                options.into_iter().map(Into::into).collect(),
+                CycleSleeper::relative(sleeper),
+            )),
+            handlers: Vec::new(),
+            buffers: Vec::new(),
+            shutdown_frame: None,
+            last_error: None,
+        }
+    }
+
+    /// Creates a new [`MultiSOEM`] with a [`DeadlineSleeper`]. Each EtherCAT thread uses its own clone of `sleeper`.
+    pub fn with_deadline_sleeper<O: Into<SOEMOptionFull>>(
+        err_handler: F,
+        options: impl IntoIterator<Item = O>,
+        sleeper: S,
+    ) -> MultiSOEM<F, S>
+    where
+        S: DeadlineSleeper,
+    {
+        MultiSOEM {
+            option: Some((
+                err_handler,
+                options.into_iter().map(Into::into).collect(),
+                CycleSleeper::absolute(sleeper),
+            )),
+            handlers: Vec::new(),
+            buffers: Vec::new(),
+            shutdown_frame: None,
+            last_error: None,
+        }
+    }
+}
+
+impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper> MultiSOEM<F, S> {
+    /// Sets the frame sent before shutting down the slaves on closing. See [`SOEM::set_shutdown_frame`].
+    ///
+    /// [`SOEM::set_shutdown_frame`]: crate::SOEM::set_shutdown_frame
+    pub fn set_shutdown_frame(&mut self, tx: Vec<TxMessage>) {
+        self.shutdown_frame = Some(tx);
+    }
+
+    /// Returns the error of the last [`Link::open`] or [`Link::close`] if it failed. See [`SOEM::last_error`].
+    ///
+    /// [`SOEM::last_error`]: crate::SOEM::last_error
+    pub fn last_error(&self) -> Option<&SOEMError> {
+        self.last_error.as_ref()
+    }
+
+    /// Returns the [`OpenReport`] of each interface in the order of the options. Returns an empty list if the link is not open.
+    pub fn open_reports(&self) -> Vec<&OpenReport> {
+        self.handlers
+            .iter()
+            .map(|(handler, _)| handler.open_report())
+            .collect()
+    }
+
+    /// Returns the current mapping between the DC system time and the host clocks of each interface in the order of the options. See [`SOEM::dc_clock`].
+    ///
+    /// The difference of [`DcClockMapping::offset`] between the interfaces is the skew of their reference clocks. Returns an empty list if the link is not open.
+    ///
+    /// [`SOEM::dc_clock`]: crate::SOEM::dc_clock
+    pub fn dc_clocks(&self) -> Vec<Option<DcClockMapping>> {
+        self.handlers
+            .iter()
+            .map(|(handler, _)| handler.dc_clock())
+            .collect()
+    }
+}
+
+fn open_handlers<F, S>(
+    err_handler: F,
+    options: Vec<SOEMOptionFull>,
+    sleeper: CycleSleeper<S>,
+    geometry: &Geometry,
+) -> Result<Vec<(SOEMHandler, Range<usize>)>, SOEMError>
+where
+    F: Fn(u16, Status) + Send + Sync + 'static,
+    S: Sleeper + Clone + Send + 'static,
+{
+    validate(&options)?;
+
+    let err_handler = Arc::new(err_handler);
+    let mut handlers = Vec::with_capacity(options.len());
+    let mut slave_offset = 0;
+    for option in options {
+        let handler = SOEMHandler::open_with_sleeper(
+            {
+                let err_handler = err_handler.clone();
+                move |slave, status| {
+                    (err_handler)(
+                        global_slave_index(slave, slave_offset),
+                        global_status(status, slave_offset),
+                    )
+                }
+            },
+            option,
+            None,
+            sleeper.clone(),
+        )?;
+        slave_offset += handler.num_slaves() as u16;
+        handlers.push(handler);
+    }
+    let ranges = device_ranges(handlers.iter().map(SOEMHandler::num_devices));
+    let num_devices = ranges.last().map_or(0, |range| range.end);
+    if !geometry.is_empty() && num_devices != geometry.len() {
+        return Err(SOEMError::SlaveNotFound(
+            num_devices as _,
+            geometry.len() as _,
+        ));
+    }
+    Ok(handlers.into_iter().zip(ranges).collect())
+}
+
+fn validate(options: &[SOEMOptionFull]) -> Result<(), SOEMError> {
+    let Some(first) = options.first() else {
+        return Err(SOEMError::NoDeviceFound);
+    };
+    options.iter().try_for_each(|option| {
+        if option.ifname.is_none() && option.mac_address.is_none() {
+            return Err(SOEMError::InterfaceNotSpecified);
+        }
+        if option.send_cycle != first.send_cycle {
+            return Err(SOEMError::CycleMismatch(
+                first.send_cycle,
+                option.send_cycle,
+            ));
+        }
+        if option.sync0_cycle != first.sync0_cycle {
+            return Err(SOEMError::CycleMismatch(
+                first.sync0_cycle,
+                option.sync0_cycle,
+            ));
+        }
+        Ok(())
+    })
+}
+
+/// Converts the index of a slave on an interface to the one counted through all interfaces, where `offset` is the number of slaves on the preceding interfaces.
+fn global_slave_index(slave: u16, offset: u16) -> u16 {
+    if slave == 0 { 0 } else { slave + offset }
+}
+
+/// Converts the slave position in `status` to the one counted through all interfaces. See [`global_slave_index`].
+fn global_status(status: Status, offset: u16) -> Status {
+    match status {
+        Status::SlaveError(err) => Status::SlaveError(SlaveError {
+            slave: global_slave_index(err.slave, offset),
+            ..err
+        }),
+        status => status,
+    }
+}
+
+/// Returns the range of the devices on each interface in the geometry.
+fn device_ranges(num_devices: impl IntoIterator<Item = usize>) -> Vec<Range<usize>> {
+    num_devices
+        .into_iter()
+        .scan(0, |offset, n| {
+            let range = *offset..*offset + n;
+            *offset = range.end;
+            Some(range)
+        })
+        .collect()
+}
+
+impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Clone + Send + 'static> Link
+    for MultiSOEM<F, S>
+{
+    fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
+        if let Some((err_handler, options, sleeper)) = self.option.take() {
+            self.handlers = record_error(
+                &mut self.last_error,
+                open_handlers(err_handler, options, sleeper, geometry),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<(), LinkError> {
+        // All interfaces are closed even if some of them fail, and the first error is returned.
+        let num_devices = self.handlers.last().map_or(0, |(_, range)| range.end);
+        let shutdown_frame = self.shutdown_frame.as_deref().filter(|tx| {
+            let matched = self.handlers.is_empty() || tx.len() == num_devices;
+            if !matched {
+                tracing::warn!(
+                    "The shutdown frame is not sent because its length ({}) does not match the number of devices ({}).",
+                    tx.len(),
+                    num_devices
+                );
+            }
+            matched
+        });
+        let results = self
+            .handlers
+            .drain(..)
+            .map(|(mut handler, range)| handler.close(shutdown_frame.map(|tx| &tx[range])))
+            .collect::<Vec<_>>();
+        record_error(
+            &mut self.last_error,
+            results.into_iter().collect::<Result<(), _>>(),
+        )
+    }
+
+    fn alloc_tx_buffer(&mut self) -> Result<Vec<TxMessage>, LinkError> {
+        let num_devices = self
+            .handlers
+            .last()
+            .map(|(_, range)| range.end)
+            .ok_or(LinkError::closed())?;
+        Ok(self
+            .buffers
+            .pop()
+            .unwrap_or_else(|| vec![TxMessage::new(); num_devices]))
+    }
+
+    fn send(&mut self, tx: Vec<TxMessage>) -> Result<(), LinkError> {
+        if !self.is_open() {
+            return Err(LinkError::closed());
+        }
+        // The frames are copied to the buffers of each interface, so `tx` can be reused immediately.
+        // All buffers are allocated before sending any of them, so that the frame is not sent to only some of the interfaces.
+        let bufs = self
+            .handlers
+            .iter_mut()
+            .map(|(handler, range)| {
+                let mut buf = handler.alloc_tx_buffer().map_err(|_| LinkError::closed())?;
+                buf.clone_from_slice(&tx[range.clone()]);
+                Ok(buf)
+            })
+            .collect::<Result<Vec<_>, LinkError>>()?;
+        self.handlers
+            .iter_mut()
+            .zip(bufs)
+            .try_for_each(|((handler, _), buf)| {
+                handler.send(buf).map_err(|_| LinkError::closed())
+            })?;
+        self.buffers.push(tx);
+        Ok(())
+    }
+
+    fn receive(&mut self, rx: &mut [RxMessage]) -> Result<(), LinkError> {
+        if self.handlers.is_empty() {
+            return Err(LinkError::closed());
+        }
+        self.handlers.iter_mut().try_for_each(|(handler, range)| {
+            receive_validated(handler, &mut rx[range.clone()]).map(|_| ())
+        })
+    }
+
+    fn is_open(&self) -> bool {
+        !self.handlers.is_empty() && self.handlers.iter().all(|(handler, _)| handler.is_open())
+    }
+}
+
+impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Clone + Send + 'static> AsyncLink
+    for MultiSOEM<F, S>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::{SOEMOption, SlaveErrorKind};
+
+    fn option(ifname: Option<&str>, send_cycle: Duration, sync0_cycle: Duration) -> SOEMOptionFull {
+        SOEMOptionFull {
+            ifname: ifname.map(String::from),
+            send_cycle,
+            sync0_cycle,
+            ..SOEMOption::default().into()
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        const CYCLE: Duration = Duration::from_millis(1);
+
+        assert!(
+            validate(&[
+                option(Some("eth0"), CYCLE, CYCLE),
+                option(Some("eth1"), CYCLE, CYCLE)
+            ])
+            .is_ok()
+        );
+        assert!(matches!(validate(&[]), Err(SOEMError::NoDeviceFound)));
+        assert!(matches!(
+            validate(&[
+                option(Some("eth0"), CYCLE, CYCLE),
+                option(None, CYCLE, CYCLE)
+            ]),
+            Err(SOEMError::InterfaceNotSpecified)
+        ));
+        assert!(matches!(
+            validate(&[option(Some("eth0"), CYCLE, CYCLE), option(Some("eth1"), CYCLE * 2, CYCLE)]),
+            Err(SOEMError::CycleMismatch(expected, actual)) if expected == CYCLE && actual == CYCLE * 2
+        ));
+        assert!(matches!(
+            validate(&[option(Some("eth0"), CYCLE, CYCLE), option(Some("eth1"), CYCLE, CYCLE * 2)]),
+            Err(SOEMError::CycleMismatch(expected, actual)) if expected == CYCLE && actual == CYCLE * 2
+        ));
+    }
+
+    #[test]
+    fn test_device_ranges() {
+        assert_eq!(vec![0..2, 2..2, 2..5], device_ranges([2, 0, 3]));
+        assert!(device_ranges([]).is_empty());
+    }
+
+    #[test]
+    fn test_global_slave_index() {
+        assert_eq!(0, global_slave_index(0, 0));
+        assert_eq!(1, global_slave_index(1, 0));
+        assert_eq!(0, global_slave_index(0, 3));
+        assert_eq!(4, global_slave_index(1, 3));
+        assert_eq!(5, global_slave_index(2, 3));
+    }
+
+    #[test]
+    fn test_global_status() {
+        let err = |slave| {
+            Status::SlaveError(SlaveError {
+                slave,
+                index: 0x1000,
+                sub_index: 1,
+                kind: SlaveErrorKind::Packet { error_code: 1 },
+            })
+        };
+        assert_eq!(err(5), global_status(err(2), 3));
+        assert_eq!(err(0), global_status(err(0), 3));
+        assert_eq!(Status::Lost, global_status(Status::Lost, 3));
+    }
+}
@@ -8,6 +8,8 @@
 //
 // You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
 
+use std::{num::NonZeroUsize, time::Duration};
+
 use spin_sleep::SpinSleeper as _SpinSleeper;
 
 use autd3_core::{
@@ -16,11 +18,18 @@ use autd3_core::{
     sleep::Sleeper,
 };
 
-use crate::inner::{SOEMHandler, SOEMOptionFull};
+use crate::{
+    error::SOEMError,
+    inner::{
+        DcClockMapping, DeviceInfo, ForeignSlave, OpenReport, RxMetadata, SOEMHandler,
+        SOEMOptionFull,
+    },
+    sleeper::{CycleSleeper, DeadlineSleeper},
+};
 
 use super::Status;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct SpinSleeper {
     inner: _SpinSleeper,
 }
@@ -35,8 +44,10 @@ impl autd3_core::sleep::Sleeper for SpinSleeper {
 ///
 /// [SOEM]: https://github.com/OpenEtherCATsociety/SOEM
 pub struct SOEM<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper> {
-    option: Option<(F, SOEMOptionFull, S)>,
+    option: Option<(F, SOEMOptionFull, CycleSleeper<S>)>,
     handler: Option<SOEMHandler>,
+    shutdown_frame: Option<Vec<TxMessage>>,
+    last_error: Option<SOEMError>,
 }
 
 impl<F: Fn(u16, Status) + Send + Sync + 'static> SOEM<F, SpinSleeper> {
@@ -54,20 +65,180 @@ impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper> SOEM<F, S> {
         sleeper: S,
     ) -> SOEM<F, S> {
         SOEM {
-            option: Some((err_handler, option.into(), sleeper)),
+            option: Some((err_handler, option.into(), CycleSleeper::relative(sleeper))),
+            handler: None,
+            shutdown_frame: None,
+            last_error: None,
+        }
+    }
+
+    /// Creates a new [`SOEM`] with a [`DeadlineSleeper`].
+    ///
+    /// The EtherCAT thread passes the absolute deadline of each cycle to the sleeper.
+    pub fn with_deadline_sleeper(
+        err_handler: F,
+        option: impl Into<SOEMOptionFull>,
+        sleeper: S,
+    ) -> SOEM<F, S>
+    where
+        S: DeadlineSleeper,
+    {
+        SOEM {
+            option: Some((err_handler, option.into(), CycleSleeper::absolute(sleeper))),
             handler: None,
+            shutdown_frame: None,
+            last_error: None,
         }
     }
+
+    /// Sets the frame sent before shutting down the slaves on closing, e.g., a frame which stops the output of all devices.
+    ///
+    /// The length of `tx` must be equal to the number of the devices.
+    pub fn set_shutdown_frame(&mut self, tx: Vec<TxMessage>) {
+        self.shutdown_frame = Some(tx);
+    }
+
+    /// Sends `tx` through the high-priority lane.
+    ///
+    /// The EtherCAT thread always drains the high-priority lane before the normal send queue, so the frame is put on the wire in the next cycle.
+    /// If `flush` is `true`, all frames waiting in the normal send queue are discarded.
+    ///
+    /// The buffer must be allocated by [`Link::alloc_tx_buffer`].
+    pub fn send_urgent(&mut self, tx: Vec<TxMessage>, flush: bool) -> Result<(), LinkError> {
+        self.handler
+            .as_mut()
+            .map_or(Err(LinkError::new("Link is closed")), |inner| {
+                inner
+                    .send_urgent(tx, flush)
+                    .map_err(|_| LinkError::closed())
+            })
+    }
+
+    /// Receives the data with the [`RxMetadata`] of the cycle in which it was received.
+    pub fn receive_with_metadata(&mut self, rx: &mut [RxMessage]) -> Result<RxMetadata, LinkError> {
+        self.handler
+            .as_mut()
+            .map_or(Err(LinkError::new("Link is closed")), |inner| {
+                receive_validated(inner, rx)
+            })
+    }
+
+    /// Receives the data and returns the indices of the devices whose inputs were not refreshed in the latest cycle.
+    ///
+    /// Unlike the other receive methods, this never fails because of stale inputs even if [`SOEMOptionFull::reject_stale_input`] is set.
+    pub fn receive_checked(&mut self, rx: &mut [RxMessage]) -> Result<Vec<usize>, LinkError> {
+        self.handler
+            .as_mut()
+            .map_or(Err(LinkError::new("Link is closed")), |inner| {
+                inner
+                    .receive(rx)
+                    .map(|(_, stale)| stale)
+                    .map_err(|_| LinkError::closed())
+            })
+    }
+
+    /// Blocks until all frames sent so far have been put on the wire.
+    ///
+    /// If `timeout` is `Some` and it elapses before that, an error is returned. Frames discarded by [`SOEM::send_urgent`] are regarded as done.
+    pub fn flush(&mut self, timeout: Option<Duration>) -> Result<(), LinkError> {
+        let inner = self.handler.as_ref().ok_or(LinkError::closed())?;
+        Ok(inner.flush(timeout)?)
+    }
+
+    /// Returns the current mapping between the DC system time of the reference clock and the host clocks.
+    ///
+    /// The mapping is continuously updated by the EtherCAT thread. Returns `None` if the link is closed or no DC time has been received yet.
+    pub fn dc_clock(&self) -> Option<DcClockMapping> {
+        self.handler.as_ref().and_then(|inner| inner.dc_clock())
+    }
+
+    /// Returns the error of the last [`Link::open`] or [`Link::close`] if it failed, e.g., to find out which slaves did not reach the required state.
+    pub fn last_error(&self) -> Option<&SOEMError> {
+        self.last_error.as_ref()
+    }
+
+    /// Returns the [`OpenReport`] of the link. Returns `None` if the link is not open.
+    pub fn open_report(&self) -> Option<&OpenReport> {
+        self.handler.as_ref().map(|inner| inner.open_report())
+    }
+
+    /// Returns the identity of all slaves on the bus. Returns an empty list if the link is closed.
+    ///
+    /// Use [`DeviceInfo::scan`] to get the list without opening the link.
+    pub fn devices(&self) -> Vec<DeviceInfo> {
+        self.handler
+            .as_ref()
+            .map(|inner| inner.devices())
+            .unwrap_or_default()
+    }
+
+    /// Returns the slaves other than AUTD devices on the bus. Returns an empty list if the link is closed.
+    ///
+    /// See also [`SOEMOptionFull::allow_foreign_slaves`].
+    pub fn foreign_slaves(&self) -> Vec<ForeignSlave> {
+        self.handler
+            .as_ref()
+            .map(|inner| inner.foreign_slaves())
+            .unwrap_or_default()
+    }
+
+    /// Reads the raw input process data of the non-AUTD slave at `position` on the bus, which starts from 1.
+    ///
+    /// The data is the [`ForeignSlave::input_bytes`] bytes containing the inputs. If the inputs are less than 8 bits, they start from [`ForeignSlave::input_start_bit`] and the other bits belong to other slaves.
+    pub fn read_foreign_inputs(&self, position: u16) -> Result<Vec<u8>, LinkError> {
+        let inner = self.handler.as_ref().ok_or(LinkError::closed())?;
+        Ok(inner.read_foreign_inputs(position)?)
+    }
+
+    /// Writes the raw output process data of the non-AUTD slave at `position` on the bus, which starts from 1.
+    ///
+    /// The length of `data` must be equal to [`ForeignSlave::output_bytes`]. The data is sent every cycle until it is overwritten.
+    /// If the outputs are less than 8 bits, only the bits from [`ForeignSlave::output_start_bit`] are written, and the other bits, which belong to other slaves, are kept.
+    pub fn write_foreign_outputs(&mut self, position: u16, data: &[u8]) -> Result<(), LinkError> {
+        let inner = self.handler.as_ref().ok_or(LinkError::closed())?;
+        Ok(inner.write_foreign_outputs(position, data)?)
+    }
+
+    /// Blocks until the EtherCAT thread has received the process data `cycles` times from now, and then receives the data with its [`RxMetadata`].
+    ///
+    /// This can be used to wait for acknowledgments without polling.
+    pub fn wait_receive(
+        &mut self,
+        rx: &mut [RxMessage],
+        cycles: NonZeroUsize,
+    ) -> Result<RxMetadata, LinkError> {
+        let inner = self.handler.as_mut().ok_or(LinkError::closed())?;
+        if !inner.wait_cycles(cycles) {
+            return Err(LinkError::closed());
+        }
+        receive_validated(inner, rx)
+    }
+
+    /// Async version of [`SOEM::wait_receive`].
+    pub async fn wait_receive_async(
+        &mut self,
+        rx: &mut [RxMessage],
+        cycles: NonZeroUsize,
+    ) -> Result<RxMetadata, LinkError> {
+        let inner = self.handler.as_mut().ok_or(LinkError::closed())?;
+        if !inner.wait_cycles_async(cycles).await {
+            return Err(LinkError::closed());
+        }
+        receive_validated(inner, rx)
+    }
 }
 
 impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Send + 'static> Link for SOEM<F, S> {
     fn open(&mut self, geometry: &Geometry) -> Result<(), LinkError> {
         if let Some((err_handler, option, sleeper)) = self.option.take() {
-            self.handler = Some(SOEMHandler::open_with_sleeper(
-                err_handler,
-                option,
-                geometry,
-                sleeper,
+            self.handler = Some(record_error(
+                &mut self.last_error,
+                SOEMHandler::open_with_sleeper(
+                    err_handler,
+                    option,
+                    (!geometry.is_empty()).then_some(geometry.len()),
+                    sleeper,
+                ),
             )?);
         }
         Ok(())
@@ -75,8 +246,10 @@ impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Send + 'static> Li
 
     fn close(&mut self) -> Result<(), LinkError> {
         self.handler.take().map_or(Ok(()), |mut handler| {
-            handler.close();
-            Ok(())
+            record_error(
+                &mut self.last_error,
+                handler.close(self.shutdown_frame.as_deref()),
+            )
         })
     }
 
@@ -100,7 +273,7 @@ impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Send + 'static> Li
         self.handler
             .as_mut()
             .map_or(Err(LinkError::new("Link is closed")), |inner| {
-                inner.receive(rx).map_err(|_| LinkError::closed())
+                receive_validated(inner, rx).map(|_| ())
             })
     }
 
@@ -109,6 +282,33 @@ impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Send + 'static> Li
     }
 }
 
+/// Keeps the error of `result` in `last_error` and converts it into [`LinkError`].
+pub(crate) fn record_error<T>(
+    last_error: &mut Option<SOEMError>,
+    result: Result<T, SOEMError>,
+) -> Result<T, LinkError> {
+    match result {
+        Ok(value) => {
+            *last_error = None;
+            Ok(value)
+        }
+        Err(err) => {
+            let link_err = LinkError::new(&err);
+            *last_error = Some(err);
+            Err(link_err)
+        }
+    }
+}
+
+pub(crate) fn receive_validated(
+    inner: &mut SOEMHandler,
+    rx: &mut [RxMessage],
+) -> Result<RxMetadata, LinkError> {
+    let (metadata, stale) = inner.receive(rx).map_err(|_| LinkError::closed())?;
+    inner.validate_inputs(stale)?;
+    Ok(metadata)
+}
+
 impl<F: Fn(u16, Status) + Send + Sync + 'static, S: Sleeper + Send + 'static> AsyncLink
     for SOEM<F, S>
 {
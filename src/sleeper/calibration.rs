@@ -0,0 +1,98 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    num::NonZeroUsize,
+    time::{Duration, Instant},
+};
+
+use super::DeadlineSleeper;
+
+/// The wake-up accuracy of a [`DeadlineSleeper`] measured by [`calibrate`].
+///
+/// The latency is the time from the deadline to the actual wake-up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Calibration {
+    /// The number of samples.
+    pub samples: usize,
+    /// The minimum latency.
+    pub min: Duration,
+    /// The mean latency.
+    pub mean: Duration,
+    /// The 99th percentile latency.
+    pub p99: Duration,
+    /// The maximum latency.
+    pub max: Duration,
+}
+
+impl Calibration {
+    fn from_latencies(mut latencies: Vec<Duration>) -> Self {
+        latencies.sort_unstable();
+        let samples = latencies.len();
+        Self {
+            samples,
+            min: latencies[0],
+            mean: latencies.iter().sum::<Duration>() / samples as u32,
+            p99: latencies[(samples * 99).div_ceil(100) - 1],
+            max: latencies[samples - 1],
+        }
+    }
+}
+
+impl std::fmt::Display for Calibration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} samples, min: {:?}, mean: {:?}, p99: {:?}, max: {:?}",
+            self.samples, self.min, self.mean, self.p99, self.max
+        )
+    }
+}
+
+/// Measures the wake-up accuracy of `sleeper` on the current machine by sleeping `samples` times with `interval`.
+///
+/// The result depends on the scheduling policy and the CPU affinity of the calling thread, so call this from a thread configured in the same way as the EtherCAT thread.
+pub fn calibrate<S: DeadlineSleeper + ?Sized>(
+    sleeper: &S,
+    interval: Duration,
+    samples: NonZeroUsize,
+) -> Calibration {
+    let mut deadline = Instant::now();
+    Calibration::from_latencies(
+        (0..samples.get())
+            .map(|_| {
+                deadline += interval;
+                sleeper.sleep_until(deadline);
+                Instant::now().saturating_duration_since(deadline)
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calibration_from_latencies() {
+        let calibration =
+            Calibration::from_latencies((1..=200).rev().map(Duration::from_micros).collect());
+        assert_eq!(
+            Calibration {
+                samples: 200,
+                min: Duration::from_micros(1),
+                mean: Duration::from_nanos(100_500),
+                p99: Duration::from_micros(198),
+                max: Duration::from_micros(200),
+            },
+            calibration
+        );
+    }
+}
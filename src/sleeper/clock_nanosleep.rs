@@ -0,0 +1,58 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::{Duration, Instant};
+
+use autd3_core::sleep::Sleeper;
+
+use super::DeadlineSleeper;
+
+/// A [`DeadlineSleeper`] using `clock_nanosleep` with `TIMER_ABSTIME` on `CLOCK_MONOTONIC`.
+///
+/// Unlike spin sleepers, this does not occupy a CPU core while sleeping.
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClockNanosleepSleeper {
+    /// The duration to spin before the deadline to compensate the wake-up latency of the kernel. The default is zero.
+    pub spin: Duration,
+}
+
+impl ClockNanosleepSleeper {
+    fn nanosleep_until(deadline: Instant) {
+        let Some(ts) = super::monotonic_timespec(deadline) else {
+            return;
+        };
+
+        while unsafe {
+            libc::clock_nanosleep(
+                libc::CLOCK_MONOTONIC,
+                libc::TIMER_ABSTIME,
+                &ts,
+                std::ptr::null_mut(),
+            )
+        } == libc::EINTR
+        {}
+    }
+}
+
+impl Sleeper for ClockNanosleepSleeper {
+    fn sleep(&self, dur: Duration) {
+        self.sleep_until(Instant::now() + dur);
+    }
+}
+
+impl DeadlineSleeper for ClockNanosleepSleeper {
+    fn sleep_until(&self, deadline: Instant) {
+        Self::nanosleep_until(deadline.checked_sub(self.spin).unwrap_or(deadline));
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
+        }
+    }
+}
@@ -0,0 +1,48 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::time::{Duration, Instant};
+
+use autd3_core::sleep::Sleeper;
+
+use super::DeadlineSleeper;
+
+/// A [`DeadlineSleeper`] which sleeps with [`std::thread::sleep`] and then spins for the last [`HybridSleeper::spin_threshold`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HybridSleeper {
+    /// The remaining time below which the sleeper spins instead of sleeping. The default is 200μs.
+    pub spin_threshold: Duration,
+}
+
+impl Default for HybridSleeper {
+    fn default() -> Self {
+        Self {
+            spin_threshold: Duration::from_micros(200),
+        }
+    }
+}
+
+impl Sleeper for HybridSleeper {
+    fn sleep(&self, dur: Duration) {
+        self.sleep_until(Instant::now() + dur);
+    }
+}
+
+impl DeadlineSleeper for HybridSleeper {
+    fn sleep_until(&self, deadline: Instant) {
+        let now = Instant::now();
+        if deadline > now + self.spin_threshold {
+            std::thread::sleep(deadline - now - self.spin_threshold);
+        }
+        while Instant::now() < deadline {
+            std::hint::spin_loop();
+        }
+    }
+}
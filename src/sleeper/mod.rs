@@ -0,0 +1,94 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+mod calibration;
+#[cfg(target_os = "linux")]
+mod clock_nanosleep;
+mod hybrid;
+mod thread_sleep;
+#[cfg(target_os = "linux")]
+mod timerfd;
+
+use std::time::Instant;
+
+use autd3_core::sleep::Sleeper;
+
+pub use calibration::{Calibration, calibrate};
+#[cfg(target_os = "linux")]
+pub use clock_nanosleep::ClockNanosleepSleeper;
+pub use hybrid::HybridSleeper;
+pub use thread_sleep::ThreadSleeper;
+#[cfg(target_os = "linux")]
+pub use timerfd::TimerFdSleeper;
+
+/// A [`Sleeper`] which can sleep until an absolute deadline on the monotonic clock.
+///
+/// Sleeping until an absolute deadline does not accumulate the error of each sleep unlike relative sleeps.
+pub trait DeadlineSleeper: Sleeper {
+    /// Sleeps until `deadline`.
+    fn sleep_until(&self, deadline: Instant);
+}
+
+/// Converts `deadline` to an absolute time on `CLOCK_MONOTONIC`. Returns `None` if `deadline` has already passed.
+///
+/// `Instant` is based on `CLOCK_MONOTONIC` on Linux, so the deadline is converted by adding the remaining time to the current clock value.
+#[cfg(target_os = "linux")]
+fn monotonic_timespec(deadline: Instant) -> Option<libc::timespec> {
+    let now = Instant::now();
+    if deadline <= now {
+        return None;
+    }
+    let remaining = deadline - now;
+
+    let mut ts = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    unsafe { libc::clock_gettime(libc::CLOCK_MONOTONIC, &mut ts) };
+    let nsec = ts.tv_nsec as u64 + remaining.subsec_nanos() as u64;
+    ts.tv_sec += (remaining.as_secs() + nsec / 1_000_000_000) as libc::time_t;
+    ts.tv_nsec = (nsec % 1_000_000_000) as _;
+    Some(ts)
+}
+
+/// A sleeper used by the EtherCAT thread to wait for the next cycle.
+#[derive(Clone)]
+pub(crate) struct CycleSleeper<S> {
+    sleeper: S,
+    sleep_until: fn(&S, Instant),
+}
+
+impl<S: Sleeper> CycleSleeper<S> {
+    pub(crate) fn relative(sleeper: S) -> Self {
+        Self {
+            sleeper,
+            sleep_until: |sleeper, deadline| {
+                let now = Instant::now();
+                if deadline > now {
+                    sleeper.sleep(deadline - now);
+                }
+            },
+        }
+    }
+
+    pub(crate) fn absolute(sleeper: S) -> Self
+    where
+        S: DeadlineSleeper,
+    {
+        Self {
+            sleeper,
+            sleep_until: S::sleep_until,
+        }
+    }
+
+    pub(crate) fn sleep_until(&self, deadline: Instant) {
+        (self.sleep_until)(&self.sleeper, deadline)
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright (c) 2022-2025 Shun Suzuki
+//
+// This file is part of autd3-link-soem.
+//
+// autd3-link-soem is free software: you can redistribute it and/or modify it under the terms of the GNU General Public License as published by the Free Software Foundation, either version 3 of the License.
+//
+// autd3-link-soem is distributed in the hope that it will be useful, but WITHOUT ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License along with Foobar. If not, see <https://www.gnu.org/licenses/>.
+
+use std::{
+    cell::OnceCell,
+    os::fd::{AsRawFd, FromRawFd, OwnedFd},
+    sync::Once,
+    time::{Duration, Instant},
+};
+
+use autd3_core::sleep::Sleeper;
+
+use super::{ClockNanosleepSleeper, DeadlineSleeper};
+
+thread_local! {
+    static TIMERFD: OnceCell<OwnedFd> = const { OnceCell::new() };
+}
+
+static WARN_FALLBACK: Once = Once::new();
+
+/// Runs `f` with the `timerfd` of the current thread, which is created on the first use.
+fn with_timerfd<R>(f: impl FnOnce(&OwnedFd) -> R) -> std::io::Result<R> {
+    TIMERFD.with(|cell| {
+        if cell.get().is_none() {
+            let fd = unsafe { libc::timerfd_create(libc::CLOCK_MONOTONIC, libc::TFD_CLOEXEC) };
+            if fd < 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            let _ = cell.set(unsafe { OwnedFd::from_raw_fd(fd) });
+        }
+        Ok(f(cell.get().unwrap()))
+    })
+}
+
+/// A [`DeadlineSleeper`] which waits for the expiration of a `timerfd` armed with an absolute time on `CLOCK_MONOTONIC`.
+///
+/// Each thread uses its own `timerfd`, so the sleeper can be cloned and shared between threads, e.g., by [`MultiSOEM`].
+/// If the `timerfd` cannot be used, the sleeper falls back to [`ClockNanosleepSleeper`].
+///
+/// [`MultiSOEM`]: crate::MultiSOEM
+#[cfg_attr(docsrs, doc(cfg(target_os = "linux")))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerFdSleeper {
+    _private: (),
+}
+
+impl TimerFdSleeper {
+    /// Creates a new [`TimerFdSleeper`].
+    ///
+    /// Returns an error if a `timerfd` cannot be created on the current thread.
+    pub fn new() -> std::io::Result<Self> {
+        with_timerfd(|_| Self { _private: () })
+    }
+}
+
+impl Sleeper for TimerFdSleeper {
+    fn sleep(&self, dur: Duration) {
+        self.sleep_until(Instant::now() + dur);
+    }
+}
+
+impl DeadlineSleeper for TimerFdSleeper {
+    fn sleep_until(&self, deadline: Instant) {
+        let Some(ts) = super::monotonic_timespec(deadline) else {
+            return;
+        };
+
+        let spec = libc::itimerspec {
+            it_interval: libc::timespec {
+                tv_sec: 0,
+                tv_nsec: 0,
+            },
+            it_value: ts,
+        };
+        let result = with_timerfd(|fd| {
+            if unsafe {
+                libc::timerfd_settime(
+                    fd.as_raw_fd(),
+                    libc::TFD_TIMER_ABSTIME,
+                    &spec,
+                    std::ptr::null_mut(),
+                )
+            } < 0
+            {
+                return Err(std::io::Error::last_os_error());
+            }
+
+            let mut expirations = 0u64;
+            loop {
+                if unsafe {
+                    libc::read(
+                        fd.as_raw_fd(),
+                        &mut expirations as *mut _ as *mut _,
+                        std::mem::size_of::<u64>(),
+                    )
+                } >= 0
+                {
+                    return Ok(());
+                }
+                let err = std::io::Error::last_os_error();
+                if err.kind() != std::io::ErrorKind::Interrupted {
+                    return Err(err);
+                }
+            }
+        })
+        .and_then(|result| result);
+        if let Err(e) = result {
+            WARN_FALLBACK.call_once(|| {
+                tracing::warn!(
+                    "Failed to wait on timerfd: {}. Falling back to clock_nanosleep.",
+                    e
+                );
+            });
+            ClockNanosleepSleeper::default().sleep_until(deadline);
+        }
+    }
+}